@@ -1,10 +1,16 @@
 #![no_std]
 
+pub mod select;
+
+pub use async_ach_barrier as barrier;
 pub use async_ach_cell as cell;
+pub use async_ach_lock as lock;
 pub use async_ach_mpmc as mpmc;
 pub use async_ach_notify as notify;
+pub use async_ach_oneshot as oneshot;
 pub use async_ach_pubsub as pubsub;
 pub use async_ach_ring as ring;
+pub use async_ach_semaphore as semaphore;
 pub use async_ach_spsc as spsc;
 pub use async_ach_waker as waker;
 pub use async_ach_watch as watch;
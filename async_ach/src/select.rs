@@ -0,0 +1,141 @@
+//! Wait on several channels at once.
+//!
+//! `Select` and `Merge` are combinators over an array of futures or streams of one type.
+//! They are kept across polls, so each listener holds a single slot in its channel's `WakerPool`
+//! instead of registering again on every poll, but every pending branch is polled on each wakeup.
+//!
+//! Sources of different types are mixed as `Pin<&mut dyn Future>` or `&mut (dyn Stream + Unpin)`
+//! after mapping their outputs to a common type, which needs neither `std` nor `alloc`:
+//!
+//! ```
+//! use async_ach::notify::Notify;
+//! use async_ach::ring::Ring;
+//! use async_ach::select::select;
+//! use core::future::Future;
+//! use core::pin::Pin;
+//! use futures_util::{pin_mut, FutureExt};
+//!
+//! #[derive(Debug, PartialEq)]
+//! enum Event {
+//!     Data(u8),
+//!     Tick,
+//! }
+//!
+//! static RING: Ring<u8, 4, 1, 1> = Ring::new();
+//! static TICK: Notify<1> = Notify::new();
+//!
+//! async fn next_event() -> (usize, Event) {
+//!     let data = RING.pop().map(Event::Data);
+//!     let tick = TICK.listen().map(|_| Event::Tick);
+//!     pin_mut!(data, tick);
+//!     let sources: [Pin<&mut dyn Future<Output = Event>>; 2] = [data, tick];
+//!     select(sources).await
+//! }
+//!
+//! RING.try_push(1).unwrap();
+//! assert_eq!(futures_executor::block_on(next_event()), (0, Event::Data(1)));
+//! ```
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_util::Stream;
+
+/// Polls the futures in turn, and returns the first ready output with its index.
+///
+/// A future is not polled again after it is ready, so the `Select` can be polled again
+/// to wait for the remaining ones, it panics if polled after all futures are ready.
+pub struct Select<F, const N: usize> {
+    futures: [F; N],
+    done: [bool; N],
+    start: usize,
+}
+impl<F, const N: usize> Select<F, N> {
+    pub const fn new(futures: [F; N]) -> Self {
+        Self {
+            futures,
+            done: [false; N],
+            start: 0,
+        }
+    }
+    /// Returns true if the future of `index` had returned its output.
+    pub fn is_done(&self, index: usize) -> bool {
+        self.done[index]
+    }
+    pub fn into_inner(self) -> [F; N] {
+        self.futures
+    }
+}
+impl<F: Future + Unpin, const N: usize> Future for Select<F, N> {
+    type Output = (usize, F::Output);
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        assert!(
+            self.done.iter().any(|x| !*x),
+            "`Select` polled after all futures are ready"
+        );
+        for i in 0..N {
+            // start from the next branch to avoid starving the others
+            let index = (self.start + i) % N;
+            if self.done[index] {
+                continue;
+            }
+            if let Poll::Ready(val) = Pin::new(&mut self.futures[index]).poll(cx) {
+                self.done[index] = true;
+                self.start = index + 1;
+                return Poll::Ready((index, val));
+            }
+        }
+        Poll::Pending
+    }
+}
+pub const fn select<F, const N: usize>(futures: [F; N]) -> Select<F, N> {
+    Select::new(futures)
+}
+
+/// Polls the streams in turn, and yields each item with the index of its stream.
+///
+/// Ends after all streams are ended.
+pub struct Merge<S, const N: usize> {
+    streams: [S; N],
+    done: [bool; N],
+    start: usize,
+}
+impl<S, const N: usize> Merge<S, N> {
+    pub const fn new(streams: [S; N]) -> Self {
+        Self {
+            streams,
+            done: [false; N],
+            start: 0,
+        }
+    }
+    pub fn into_inner(self) -> [S; N] {
+        self.streams
+    }
+}
+impl<S: Stream + Unpin, const N: usize> Stream for Merge<S, N> {
+    type Item = (usize, S::Item);
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        for i in 0..N {
+            let index = (self.start + i) % N;
+            if self.done[index] {
+                continue;
+            }
+            match Pin::new(&mut self.streams[index]).poll_next(cx) {
+                Poll::Ready(Some(val)) => {
+                    self.start = index + 1;
+                    return Poll::Ready(Some((index, val)));
+                }
+                Poll::Ready(None) => self.done[index] = true,
+                Poll::Pending => {}
+            }
+        }
+        if self.done.iter().all(|x| *x) {
+            Poll::Ready(None)
+        } else {
+            Poll::Pending
+        }
+    }
+}
+pub const fn merge<S, const N: usize>(streams: [S; N]) -> Merge<S, N> {
+    Merge::new(streams)
+}
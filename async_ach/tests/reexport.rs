@@ -0,0 +1,14 @@
+use async_ach::notify::IntrusiveNotify;
+use async_ach::ring::Ring;
+use async_ach::spsc::pipe::Pipe;
+
+#[test]
+fn test_workspace_crates() {
+    static RING: Ring<u8, 2, 0, 0, IntrusiveNotify, IntrusiveNotify> = Ring::new();
+    static PIPE: Pipe<4> = Pipe::new();
+
+    RING.try_push(1).unwrap();
+    assert_eq!(RING.peek().unwrap(), 1);
+    let mut writer = PIPE.take_writer().unwrap();
+    assert_eq!(writer.try_write(b"ach"), Ok(3));
+}
@@ -0,0 +1,100 @@
+use async_ach::notify::Notify;
+use async_ach::ring::Ring;
+use async_ach::select::{merge, select};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+use futures_util::{pin_mut, Stream};
+
+#[test]
+fn test_select() {
+    static NOTIFY1: Notify<1> = Notify::new();
+    static NOTIFY2: Notify<1> = Notify::new();
+    let mut cx = task::noop_context();
+
+    let mut select = select([NOTIFY1.listen(), NOTIFY2.listen()]);
+    assert!(Pin::new(&mut select).poll(&mut cx).is_pending());
+    NOTIFY2.notify_one();
+    assert_eq!(Pin::new(&mut select).poll(&mut cx), Poll::Ready((1, ())));
+    assert!(Pin::new(&mut select).poll(&mut cx).is_pending());
+    NOTIFY1.notify_one();
+    assert_eq!(Pin::new(&mut select).poll(&mut cx), Poll::Ready((0, ())));
+}
+
+#[test]
+fn test_merge() {
+    static NOTIFY1: Notify<1> = Notify::new();
+    static NOTIFY2: Notify<2> = Notify::new();
+    let mut cx = task::noop_context();
+
+    let mut listener1 = NOTIFY1.listen();
+    let mut listener2 = NOTIFY2.listen();
+    let streams: [&mut (dyn Stream<Item = ()> + Unpin); 2] = [&mut listener1, &mut listener2];
+    let mut merge = merge(streams);
+    assert!(Pin::new(&mut merge).poll_next(&mut cx).is_pending());
+    NOTIFY1.notify_one();
+    NOTIFY2.notify_one();
+    assert_eq!(
+        Pin::new(&mut merge).poll_next(&mut cx),
+        Poll::Ready(Some((0, ())))
+    );
+    assert_eq!(
+        Pin::new(&mut merge).poll_next(&mut cx),
+        Poll::Ready(Some((1, ())))
+    );
+    assert!(Pin::new(&mut merge).poll_next(&mut cx).is_pending());
+}
+
+#[test]
+fn test_select_recv() {
+    static RING1: Ring<u8, 1, 1, 2> = Ring::new();
+    static RING2: Ring<u8, 1, 1, 2> = Ring::new();
+    let mut cx = task::noop_context();
+
+    let pop1 = RING1.pop();
+    let pop2 = RING2.pop();
+    pin_mut!(pop1, pop2);
+    let mut select = select([pop1, pop2]);
+    assert!(Pin::new(&mut select).poll(&mut cx).is_pending());
+    RING2.try_push(2).unwrap();
+    assert_eq!(Pin::new(&mut select).poll(&mut cx), Poll::Ready((1, 2)));
+    assert!(select.is_done(1));
+    // the finished `pop` is not polled again
+    assert!(Pin::new(&mut select).poll(&mut cx).is_pending());
+    RING1.try_push(1).unwrap();
+    assert_eq!(Pin::new(&mut select).poll(&mut cx), Poll::Ready((0, 1)));
+}
+
+#[test]
+fn test_merge_heterogeneous() {
+    use futures_util::StreamExt;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Data(u8),
+        Tick,
+    }
+    static RING: Ring<u8, 2, 1, 1> = Ring::new();
+    static TICK: Notify<1> = Notify::new();
+    let mut cx = task::noop_context();
+
+    let mut tick = TICK.listen().map(|_| Event::Tick);
+    let data = futures_util::stream::unfold((), |_| async {
+        Some((Event::Data(RING.pop().await), ()))
+    });
+    pin_mut!(data);
+    let streams: [&mut (dyn Stream<Item = Event> + Unpin); 2] = [&mut tick, &mut data];
+    let mut merge = merge(streams);
+    assert!(Pin::new(&mut merge).poll_next(&mut cx).is_pending());
+    TICK.notify_one();
+    assert_eq!(
+        Pin::new(&mut merge).poll_next(&mut cx),
+        Poll::Ready(Some((0, Event::Tick)))
+    );
+    RING.try_push(3).unwrap();
+    assert_eq!(
+        Pin::new(&mut merge).poll_next(&mut cx),
+        Poll::Ready(Some((1, Event::Data(3))))
+    );
+}
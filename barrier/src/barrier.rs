@@ -0,0 +1,88 @@
+use async_ach_notify::{Listener, Notify};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use core::task::{Context, Poll};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarrierWaitResult(bool);
+impl BarrierWaitResult {
+    /// Returns true if this task is the last one arrived in the generation.
+    ///
+    /// Exactly one task is the leader in each generation.
+    pub fn is_leader(&self) -> bool {
+        self.0
+    }
+}
+
+/// Wait until `n` tasks are arrived.
+///
+/// `W` is the number of tasks which can wait at the same time.
+/// The barrier will be reused after all tasks of a generation are released.
+pub struct Barrier<const W: usize> {
+    n: usize,
+    arrived: AtomicUsize,
+    released: AtomicUsize,
+    waiters: Notify<W>,
+}
+impl<const W: usize> Barrier<W> {
+    pub const fn new(n: usize) -> Self {
+        Self {
+            n: if n == 0 { 1 } else { n },
+            arrived: AtomicUsize::new(0),
+            released: AtomicUsize::new(0),
+            waiters: Notify::new(),
+        }
+    }
+    /// Wait for all tasks of the generation.
+    ///
+    /// The task is arrived at the first poll, and cancelling it will not revoke the arrival.
+    pub fn wait(&self) -> Wait<'_, W> {
+        Wait {
+            parent: self,
+            generation: None,
+            wait: self.waiters.listen(),
+        }
+    }
+    fn is_released(&self, generation: usize) -> bool {
+        self.released.load(SeqCst) > generation
+    }
+}
+
+pub struct Wait<'a, const W: usize> {
+    parent: &'a Barrier<W>,
+    generation: Option<usize>,
+    wait: Listener<'a, W>,
+}
+impl<'a, const W: usize> Future for Wait<'a, W> {
+    type Output = BarrierWaitResult;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let parent = self.parent;
+        let generation = if let Some(generation) = self.generation {
+            generation
+        } else {
+            let ticket = parent.arrived.fetch_add(1, SeqCst);
+            let generation = ticket / parent.n;
+            if ticket % parent.n == parent.n - 1 {
+                parent.released.fetch_max(generation + 1, SeqCst);
+                parent.waiters.notify_waiters();
+                return Poll::Ready(BarrierWaitResult(true));
+            }
+            self.generation = Some(generation);
+            generation
+        };
+        loop {
+            if parent.is_released(generation) {
+                return Poll::Ready(BarrierWaitResult(false));
+            }
+            if Pin::new(&mut self.wait).poll(cx).is_pending() {
+                // the leader may be arrived before the waker registered
+                return if parent.is_released(generation) {
+                    Poll::Ready(BarrierWaitResult(false))
+                } else {
+                    Poll::Pending
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use async_ach_notify::{Listener, Notify};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use core::task::{Context, Poll};
+
+/// Wait until the count reaches zero.
+///
+/// `W` is the number of tasks which can wait at the same time.
+/// The latch can be re-armed by `reset` after it is released.
+pub struct CountDownLatch<const W: usize> {
+    count: AtomicUsize,
+    generation: AtomicUsize,
+    waiters: Notify<W>,
+}
+impl<const W: usize> CountDownLatch<W> {
+    pub const fn new(count: usize) -> Self {
+        Self {
+            count: AtomicUsize::new(count),
+            generation: AtomicUsize::new(0),
+            waiters: Notify::new(),
+        }
+    }
+    /// Returns the current count.
+    pub fn count(&self) -> usize {
+        self.count.load(SeqCst)
+    }
+    /// Decrements the count, and wake all waiters if it reaches zero.
+    ///
+    /// It does nothing if the count is already zero.
+    pub fn count_down(&self) {
+        if self
+            .count
+            .fetch_update(SeqCst, SeqCst, |x| x.checked_sub(1))
+            == Ok(1)
+        {
+            self.generation.fetch_add(1, SeqCst);
+            self.waiters.notify_waiters();
+        }
+    }
+    /// Re-arm the latch with `count` for the next generation.
+    ///
+    /// Returns false if the latch is not released yet.
+    pub fn reset(&self, count: usize) -> bool {
+        self.count
+            .compare_exchange(0, count, SeqCst, SeqCst)
+            .is_ok()
+    }
+    /// Wait for the count reaches zero.
+    ///
+    /// Waiters created before the release are woken, even if the latch is reset before they polled.
+    pub fn wait(&self) -> LatchWait<'_, W> {
+        LatchWait {
+            parent: self,
+            generation: self.generation.load(SeqCst),
+            wait: self.waiters.listen(),
+        }
+    }
+    fn is_released(&self, generation: usize) -> bool {
+        self.generation.load(SeqCst) != generation || self.count() == 0
+    }
+}
+
+pub struct LatchWait<'a, const W: usize> {
+    parent: &'a CountDownLatch<W>,
+    generation: usize,
+    wait: Listener<'a, W>,
+}
+impl<'a, const W: usize> Future for LatchWait<'a, W> {
+    type Output = ();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            if self.parent.is_released(self.generation) {
+                return Poll::Ready(());
+            }
+            if Pin::new(&mut self.wait).poll(cx).is_pending() {
+                // the count may reach zero before the waker registered
+                return if self.parent.is_released(self.generation) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                };
+            }
+        }
+    }
+}
@@ -0,0 +1,7 @@
+#![no_std]
+
+mod barrier;
+mod latch;
+
+pub use barrier::*;
+pub use latch::*;
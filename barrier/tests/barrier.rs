@@ -0,0 +1,32 @@
+use async_ach_barrier::Barrier;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static BARRIER: Barrier<2> = Barrier::new(3);
+    let mut cx = task::noop_context();
+
+    for _ in 0..2 {
+        let mut wait1 = BARRIER.wait();
+        let mut wait2 = BARRIER.wait();
+        let mut wait3 = BARRIER.wait();
+        assert!(Pin::new(&mut wait1).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut wait2).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut wait1).poll(&mut cx).is_pending());
+        match Pin::new(&mut wait3).poll(&mut cx) {
+            Poll::Ready(ret) => assert!(ret.is_leader()),
+            Poll::Pending => panic!(),
+        }
+        match Pin::new(&mut wait1).poll(&mut cx) {
+            Poll::Ready(ret) => assert!(!ret.is_leader()),
+            Poll::Pending => panic!(),
+        }
+        match Pin::new(&mut wait2).poll(&mut cx) {
+            Poll::Ready(ret) => assert!(!ret.is_leader()),
+            Poll::Pending => panic!(),
+        }
+    }
+}
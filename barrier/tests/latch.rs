@@ -0,0 +1,28 @@
+use async_ach_barrier::CountDownLatch;
+use core::future::Future;
+use core::pin::Pin;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static LATCH: CountDownLatch<2> = CountDownLatch::new(2);
+    let mut cx = task::noop_context();
+
+    let mut wait1 = LATCH.wait();
+    assert!(Pin::new(&mut wait1).poll(&mut cx).is_pending());
+    assert!(!LATCH.reset(1));
+    LATCH.count_down();
+    assert!(Pin::new(&mut wait1).poll(&mut cx).is_pending());
+    let mut wait2 = LATCH.wait();
+    LATCH.count_down();
+    assert_eq!(LATCH.count(), 0);
+    assert!(Pin::new(&mut wait1).poll(&mut cx).is_ready());
+
+    assert!(LATCH.reset(1));
+    let mut wait3 = LATCH.wait();
+    assert!(Pin::new(&mut wait2).poll(&mut cx).is_ready());
+    assert!(Pin::new(&mut wait3).poll(&mut cx).is_pending());
+    LATCH.count_down();
+    LATCH.count_down();
+    assert!(Pin::new(&mut wait3).poll(&mut cx).is_ready());
+}
@@ -1,27 +1,60 @@
 #![no_std]
 
+mod once;
+
+pub use once::OnceCell;
+
 use ach_cell as ach;
-use ach_util::Error;
-use async_ach_notify::{Listener, Notify};
+use ach_util::{Error, MemoryState};
+use async_ach_notify::{Notifier, Notify};
+use atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst};
 use core::future::Future;
-use core::ops::Deref;
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
 use core::pin::Pin;
 use core::task::{Context, Poll};
+use futures_util::future::poll_fn;
+use futures_util::pin_mut;
 
-pub struct Ref<'a, T, const MP: usize, const MC: usize> {
-    parent: &'a Cell<T, MP, MC>,
-    val: ach::Ref<'a, T>,
+#[cfg(loom)]
+use async_ach_notify::loom_atomic::{self as atomic, spin_loop};
+#[cfg(not(loom))]
+use core::{hint::spin_loop, sync::atomic};
+
+pub struct Ref<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    /// released before notifying on drop
+    val: ManuallyDrop<ach::Ref<'a, T>>,
 }
-impl<'a, T, const MP: usize, const MC: usize> Deref for Ref<'a, T, MP, MC> {
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Deref
+    for Ref<'a, T, MP, MC, C, P>
+{
     type Target = ach::Ref<'a, T>;
     fn deref(&self) -> &Self::Target {
         &self.val
     }
 }
-impl<'a, T, const MP: usize, const MC: usize> Drop for Ref<'a, T, MP, MC> {
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Drop
+    for Ref<'a, T, MP, MC, C, P>
+{
     fn drop(&mut self) {
-        if self.val.ref_num() == Ok(1) {
-            if self.val.will_remove() {
+        let remove = self.val.will_remove();
+        // the woken operation checks the references again, so release this one first
+        unsafe { ManuallyDrop::drop(&mut self.val) };
+        let last = match self.parent.val.ref_num() {
+            Ok(num) => num == 0,
+            // removed
+            Err(_) => true,
+        };
+        if last {
+            if remove {
                 self.parent.consumer.notify_one();
             } else {
                 self.parent.producer.notify_one();
@@ -30,62 +63,218 @@ impl<'a, T, const MP: usize, const MC: usize> Drop for Ref<'a, T, MP, MC> {
     }
 }
 
-pub struct Cell<T, const MP: usize, const MC: usize> {
+/// Exclusive access to the value, which is moved out of the cell.
+///
+/// The value is set back on drop, and both sides are notified if any operation was rejected.
+struct Locked<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    val: Option<T>,
+    /// notify producers on drop, even if no operation was rejected
+    notify: bool,
+}
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Locked<'a, T, MP, MC, C, P>
+{
+    /// Sets the value back, and refers it before unlocking.
+    fn into_ref(mut self) -> Option<Ref<'a, T, MP, MC, C, P>> {
+        let val = self.val.take()?;
+        let parent = self.parent;
+        // it is locked, so the cell is uninitialized and not refered.
+        let _ = parent.val.try_set(val);
+        let val = parent.val.try_get().ok()?;
+        Some(Ref {
+            parent,
+            val: ManuallyDrop::new(val),
+        })
+    }
+}
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Drop
+    for Locked<'a, T, MP, MC, C, P>
+{
+    fn drop(&mut self) {
+        if let Some(val) = self.val.take() {
+            let _ = self.parent.val.try_set(val);
+        }
+        self.parent.locked.store(false, SeqCst);
+        let contended = self.parent.contended.swap(false, SeqCst);
+        if contended || self.notify {
+            self.parent.producer.notify_one();
+        }
+        if contended {
+            self.parent.consumer.notify_one();
+        }
+    }
+}
+
+/// The running initializer of `get_or_init`.
+///
+/// The next initializer is notified on drop, in case this one is canceled.
+struct Initializing<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+}
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Drop
+    for Initializing<'a, T, MP, MC, C, P>
+{
+    fn drop(&mut self) {
+        self.parent.initializing.store(false, SeqCst);
+        self.parent.producer.notify_one();
+    }
+}
+
+/// A mutable reference to the value of `Cell`.
+///
+/// Other operations are rejected until it is dropped.
+pub struct RefMut<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    locked: Locked<'a, T, MP, MC, C, P>,
+}
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Deref
+    for RefMut<'a, T, MP, MC, C, P>
+{
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.locked.val.as_ref().unwrap()
+    }
+}
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> DerefMut
+    for RefMut<'a, T, MP, MC, C, P>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.locked.val.as_mut().unwrap()
+    }
+}
+
+/// Waits for a notice of either side.
+async fn notified<A: Future, B: Future>(mut wait_p: Pin<&mut A>, mut wait_c: Pin<&mut B>) {
+    poll_fn(|cx| {
+        let p = wait_p.as_mut().poll(cx).is_ready();
+        let c = wait_c.as_mut().poll(cx).is_ready();
+        if p || c {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+fn busy<I>(input: I) -> Error<I> {
+    Error {
+        state: MemoryState::Regaining,
+        input,
+        retry: true,
+    }
+}
+
+/// `C` and `P` are the notify backends of consumers and producers.
+///
+/// Use `IntrusiveNotify` if the number of waiters is unknown, then `MP` and `MC` are ignored.
+pub struct Cell<T, const MP: usize, const MC: usize, C = Notify<MP>, P = Notify<MC>> {
     val: ach::Cell<T>,
-    consumer: Notify<MP>,
-    producer: Notify<MC>,
+    consumer: C,
+    producer: P,
+    /// locked by an update
+    locked: AtomicBool,
+    /// number of operations in progress
+    active: AtomicUsize,
+    /// an operation was rejected by the lock
+    contended: AtomicBool,
+    /// an initializer of `get_or_init` is running
+    initializing: AtomicBool,
+}
+impl<T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Default
+    for Cell<T, MP, MC, C, P>
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
-impl<T, const MP: usize, const MC: usize> Cell<T, MP, MC> {
+impl<T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Cell<T, MP, MC, C, P> {
     pub const fn new() -> Self {
         Self {
             val: ach::Cell::new(),
-            consumer: Notify::new(),
-            producer: Notify::new(),
+            consumer: C::NEW,
+            producer: P::NEW,
+            locked: AtomicBool::new(false),
+            active: AtomicUsize::new(0),
+            contended: AtomicBool::new(false),
+            initializing: AtomicBool::new(false),
         }
     }
     pub const fn new_with(val: T) -> Self {
         Self {
             val: ach::Cell::new_with(val),
-            consumer: Notify::new(),
-            producer: Notify::new(),
+            consumer: C::NEW,
+            producer: P::NEW,
+            locked: AtomicBool::new(false),
+            active: AtomicUsize::new(0),
+            contended: AtomicBool::new(false),
+            initializing: AtomicBool::new(false),
         }
     }
 }
-impl<T: Unpin, const MP: usize, const MC: usize> Cell<T, MP, MC> {
+impl<T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Cell<T, MP, MC, C, P> {
+    /// Gets a reference to the value, without checking or referring it.
+    ///
+    /// # Safety
+    ///
+    /// The cell must be initialized, and the value must not be taken, replaced or locked
+    /// while the reference is alive.
+    /// `try_get_mut`, `try_update`, `try_compare_and_swap` and `get_or_init` move the value
+    /// out of the cell while it is locked, so peeking a locked cell reads uninitialized memory.
     pub unsafe fn peek(&self) -> &T {
         self.val.peek()
     }
     /// Tries to get a reference to the value of the Cell.
     ///
     /// Returns Err if the cell is uninitialized or in critical section.
-    pub fn try_get(&self) -> Result<Ref<T, MP, MC>, Error<()>> {
-        self.val.try_get().map(|x| Ref {
+    pub fn try_get(&self) -> Result<Ref<'_, T, MP, MC, C, P>, Error<()>> {
+        self.op((), |_| self.val.try_get()).map(|x| Ref {
             parent: self,
-            val: x,
+            val: ManuallyDrop::new(x),
         })
     }
     /// Tries to get a reference to the value of the Cell.
     ///
-    /// Returns Err if the cell is uninitialized.
-    pub fn get(&self) -> Get<'_, T, MP, MC> {
+    /// Returns Err if the cell is uninitialized, use `wait_get` to wait until it is set.
+    pub fn get(&self) -> Get<'_, T, MP, MC, C, P> {
         Get {
             parent: self,
             wait_p: self.producer.listen(),
         }
     }
+    /// Waits until the Cell is set, and gets a reference to the value.
+    pub fn wait_get(&self) -> WaitGet<'_, T, MP, MC, C, P> {
+        WaitGet {
+            parent: self,
+            wait_p: self.producer.listen(),
+        }
+    }
+    /// Waits until the value of the Cell is taken.
+    pub fn wait_empty(&self) -> WaitEmpty<'_, T, MP, MC, C, P> {
+        WaitEmpty {
+            parent: self,
+            wait_c: self.consumer.listen(),
+            parked: false,
+        }
+    }
     /// Sets the value of the Cell to the argument value.
     ///
     /// Returns Err if the value is refered, initialized or in critical section.
     pub fn try_set(&self, val: T) -> Result<(), Error<T>> {
-        self.val.try_set(val).map(|x| {
-            self.producer.notify_one();
-            x
-        })
+        self.op(val, |val| self.val.try_set(val))
+            .inspect(|_| self.producer.notify_one())
     }
     /// Sets the value of the Cell to the argument value.
     ///
     /// Returns Err if the value is refered or initialized.
-    pub fn set(&self, val: T) -> Set<'_, T, MP, MC> {
+    pub fn set(&self, val: T) -> Set<'_, T, MP, MC, C, P> {
         Set {
             parent: self,
             wait_c: self.consumer.listen(),
@@ -96,13 +285,11 @@ impl<T: Unpin, const MP: usize, const MC: usize> Cell<T, MP, MC> {
     ///
     /// Returns Err if the cell is refered or in critical section.
     pub fn try_take(&self) -> Result<Option<T>, Error<()>> {
-        self.val.try_take().map(|x| {
-            self.consumer.notify_one();
-            x
-        })
+        self.op((), |_| self.val.try_take())
+            .inspect(|_| self.consumer.notify_one())
     }
     /// Takes ownership of the current value, leaving the cell uninitialized.
-    pub fn take(&self) -> Take<'_, T, MP, MC> {
+    pub fn take(&self) -> Take<'_, T, MP, MC, C, P> {
         Take {
             parent: self,
             wait_p: self.producer.listen(),
@@ -113,13 +300,11 @@ impl<T: Unpin, const MP: usize, const MC: usize> Cell<T, MP, MC> {
     ///
     /// Returns Err if the value is refered or in critical section.
     pub fn try_replace(&self, val: T) -> Result<Option<T>, Error<T>> {
-        self.val.try_replace(val).map(|x| {
-            self.producer.notify_one();
-            x
-        })
+        self.op(val, |val| self.val.try_replace(val))
+            .inspect(|_| self.producer.notify_one())
     }
     /// Replaces the contained value with value, and returns the old contained value.
-    pub fn replace(&self, val: T) -> Replace<'_, T, MP, MC> {
+    pub fn replace(&self, val: T) -> Replace<'_, T, MP, MC, C, P> {
         Replace {
             parent: self,
             wait_p: self.producer.listen(),
@@ -127,96 +312,477 @@ impl<T: Unpin, const MP: usize, const MC: usize> Cell<T, MP, MC> {
             val: Some(val),
         }
     }
+    /// Tries to get a mutable reference to the value of the Cell.
+    ///
+    /// Returns Err if the cell is uninitialized, refered or in critical section.
+    pub fn try_get_mut(&self) -> Result<RefMut<'_, T, MP, MC, C, P>, Error<()>> {
+        let mut locked = self.try_lock(false)?;
+        locked.notify = true;
+        Ok(RefMut { locked })
+    }
+    /// Gets a mutable reference to the value of the Cell, after all references are dropped.
+    ///
+    /// Returns Err if the cell is uninitialized.
+    pub async fn get_mut(&self) -> Result<RefMut<'_, T, MP, MC, C, P>, Error<()>> {
+        let wait_p = self.producer.listen();
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_p, wait_c);
+        loop {
+            match self.try_get_mut() {
+                Err(err) if err.retry => {}
+                ret => return ret,
+            }
+            notified(wait_p.as_mut(), wait_c.as_mut()).await;
+        }
+    }
+    /// Updates the value in place.
+    ///
+    /// Returns Err with `f` if the cell is uninitialized, refered or in critical section.
+    pub fn try_update<R, F: FnOnce(&mut T) -> R>(&self, f: F) -> Result<R, Error<F>> {
+        match self.try_lock(false) {
+            Ok(mut locked) => {
+                locked.notify = true;
+                Ok(f(locked.val.as_mut().unwrap()))
+            }
+            Err(err) => Err(Error {
+                state: err.state,
+                input: f,
+                retry: err.retry,
+            }),
+        }
+    }
+    /// Updates the value in place.
+    ///
+    /// Returns Err with `f` if the cell is uninitialized.
+    pub async fn update<R, F: FnOnce(&mut T) -> R>(&self, mut f: F) -> Result<R, Error<F>> {
+        let wait_p = self.producer.listen();
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_p, wait_c);
+        loop {
+            match self.try_update(f) {
+                Err(err) if err.retry => f = err.input,
+                ret => return ret,
+            }
+            notified(wait_p.as_mut(), wait_c.as_mut()).await;
+        }
+    }
+    /// Gets a reference to the value, or initializes it by `f` if the cell is uninitialized.
+    ///
+    /// Only one initializer runs at a time, the others wait for it.
+    /// The cell is not locked while `f` runs, so other operations go on;
+    /// if the cell is set meanwhile, the initialized value is dropped.
+    /// If the initializer is canceled, the next one runs.
+    pub async fn get_or_init<F, Fut>(&self, f: F) -> Ref<'_, T, MP, MC, C, P>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = T>,
+    {
+        let wait_p = self.producer.listen();
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_p, wait_c);
+        let mut f = Some(f);
+        let mut initializing = None;
+        let mut val = None;
+        loop {
+            if let Ok(v) = self.try_get() {
+                return v;
+            }
+            if initializing.is_none() {
+                initializing = self.try_initialize();
+            }
+            if initializing.is_some() {
+                if let Some(init) = f.take() {
+                    val = Some(init().await);
+                }
+                if let Ok(mut locked) = self.try_lock(true) {
+                    if locked.val.is_none() {
+                        locked.val = val.take();
+                        locked.notify = true;
+                    }
+                    if let Some(v) = locked.into_ref() {
+                        return v;
+                    }
+                }
+            }
+            notified(wait_p.as_mut(), wait_c.as_mut()).await;
+        }
+    }
+
+    /// Runs an operation, it fails if the cell is locked.
+    fn op<I, R>(&self, input: I, f: impl FnOnce(I) -> Result<R, Error<I>>) -> Result<R, Error<I>> {
+        self.active.fetch_add(1, SeqCst);
+        let ret = if self.is_locked() {
+            Err(busy(input))
+        } else {
+            f(input)
+        };
+        self.active.fetch_sub(1, SeqCst);
+        ret
+    }
+    /// Returns Err if the cell is initialized or in critical section.
+    fn try_empty(&self) -> Result<(), Error<()>> {
+        self.op((), |_| match self.val.ref_num() {
+            Err(MemoryState::Uninitialized) => Ok(()),
+            Ok(_) => Err(Error {
+                state: MemoryState::Initialized,
+                input: (),
+                retry: true,
+            }),
+            Err(state) => Err(Error {
+                state,
+                input: (),
+                retry: true,
+            }),
+        })
+    }
+    /// Claims the initializer of `get_or_init`.
+    fn try_initialize(&self) -> Option<Initializing<'_, T, MP, MC, C, P>> {
+        self.initializing
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .ok()?;
+        Some(Initializing { parent: self })
+    }
+    /// Returns true if it is locked, and the lock will notify both sides on unlocking.
+    fn is_locked(&self) -> bool {
+        if !self.locked.load(SeqCst) {
+            return false;
+        }
+        self.contended.store(true, SeqCst);
+        // unlocking clears `locked` before `contended`, so check again to not miss the notice.
+        self.locked.load(SeqCst)
+    }
+    /// Locks the cell and moves the value out.
+    ///
+    /// The cell may be uninitialized if `empty`.
+    ///
+    /// Notice: `Spin` until the operations in progress are finished, they never wait.
+    fn try_lock(&self, empty: bool) -> Result<Locked<'_, T, MP, MC, C, P>, Error<()>> {
+        while self
+            .locked
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .is_err()
+        {
+            if self.is_locked() {
+                return Err(busy(()));
+            }
+        }
+        let mut locked = Locked {
+            parent: self,
+            val: None,
+            notify: false,
+        };
+        // `op` checks `locked` after increasing `active`, so no new operation will start.
+        while self.active.load(SeqCst) != 0 {
+            spin_loop();
+        }
+        match self.val.ref_num() {
+            Ok(0) => match self.val.try_take() {
+                Ok(Some(val)) => {
+                    locked.val = Some(val);
+                    Ok(locked)
+                }
+                Ok(None) if empty => Ok(locked),
+                Ok(None) => Err(Error {
+                    state: MemoryState::Uninitialized,
+                    input: (),
+                    retry: false,
+                }),
+                Err(err) => Err(err),
+            },
+            Ok(_) => Err(busy(())),
+            Err(MemoryState::Uninitialized) if empty => Ok(locked),
+            Err(state) => Err(Error {
+                state,
+                input: (),
+                retry: state.is_transient(),
+            }),
+        }
+    }
+}
+impl<T: Unpin + PartialEq, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Cell<T, MP, MC, C, P>
+{
+    /// Replaces the value with `new` if it is equal to `expected`, and returns the old value.
+    ///
+    /// Returns Err with `new` if the value is not equal, uninitialized, refered or in critical section.
+    pub fn try_compare_and_swap(&self, expected: &T, new: T) -> Result<T, Error<T>> {
+        let mut locked = match self.try_lock(false) {
+            Ok(v) => v,
+            Err(err) => {
+                return Err(Error {
+                    state: err.state,
+                    input: new,
+                    retry: err.retry,
+                })
+            }
+        };
+        let val = locked.val.as_mut().unwrap();
+        if val == expected {
+            locked.notify = true;
+            Ok(core::mem::replace(val, new))
+        } else {
+            Err(Error {
+                state: MemoryState::Initialized,
+                input: new,
+                retry: false,
+            })
+        }
+    }
+    /// Replaces the value with `new` if it is equal to `expected`, and returns the old value.
+    ///
+    /// Returns Err with `new` if the value is not equal or uninitialized.
+    pub async fn compare_and_swap(&self, expected: &T, mut new: T) -> Result<T, Error<T>> {
+        let wait_p = self.producer.listen();
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_p, wait_c);
+        loop {
+            match self.try_compare_and_swap(expected, new) {
+                Err(err) if err.retry => new = err.input,
+                ret => return ret,
+            }
+            notified(wait_p.as_mut(), wait_c.as_mut()).await;
+        }
+    }
 }
 
-pub struct Get<'a, T, const MP: usize, const MC: usize> {
-    parent: &'a Cell<T, MP, MC>,
-    wait_p: Listener<'a, MC>,
-}
-impl<'a, T: Unpin, const MP: usize, const MC: usize> Future for Get<'a, T, MP, MC> {
-    type Output = Result<Ref<'a, T, MP, MC>, Error<()>>;
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        match self.parent.try_get() {
-            Ok(v) => Poll::Ready(Ok(v)),
-            Err(err) if err.retry => {
-                if Pin::new(&mut self.wait_p).poll(cx).is_ready() {
-                    self.poll(cx)
-                } else {
-                    Poll::Pending
+/// Future of `Cell::get`.
+///
+/// It is cancel safe, dropping it before completion has no effect on the cell.
+pub struct Get<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    wait_p: P::Listener<'a>,
+}
+impl<'a, T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Future
+    for Get<'a, T, MP, MC, C, P>
+{
+    type Output = Result<Ref<'a, T, MP, MC, C, P>, Error<()>>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the listeners are never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            match this.parent.try_get() {
+                Ok(v) => return Poll::Ready(Ok(v)),
+                Err(err) if err.retry => {
+                    if unsafe { Pin::new_unchecked(&mut this.wait_p) }
+                        .poll(cx)
+                        .is_pending()
+                    {
+                        return Poll::Pending;
+                    }
                 }
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+    }
+}
+/// Future of `Cell::wait_get`.
+///
+/// It is cancel safe, dropping it before completion has no effect on the cell.
+pub struct WaitGet<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    wait_p: P::Listener<'a>,
+}
+impl<'a, T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Future
+    for WaitGet<'a, T, MP, MC, C, P>
+{
+    type Output = Ref<'a, T, MP, MC, C, P>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the listeners are never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            if let Ok(v) = this.parent.try_get() {
+                // the notice is passed on when the `Ref` is dropped
+                return Poll::Ready(v);
+            }
+            if unsafe { Pin::new_unchecked(&mut this.wait_p) }
+                .poll(cx)
+                .is_pending()
+            {
+                return Poll::Pending;
             }
-            Err(err) => Poll::Ready(Err(err)),
         }
     }
 }
-pub struct Set<'a, T, const MP: usize, const MC: usize> {
-    parent: &'a Cell<T, MP, MC>,
-    wait_c: Listener<'a, MP>,
+/// Future of `Cell::wait_empty`.
+///
+/// It is cancel safe, dropping it before completion has no effect on the cell.
+pub struct WaitEmpty<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    wait_c: C::Listener<'a>,
+    parked: bool,
+}
+impl<'a, T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Future
+    for WaitEmpty<'a, T, MP, MC, C, P>
+{
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the listeners are never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            if this.parent.try_empty().is_ok() {
+                if this.parked {
+                    // the notice may be sent to this waiter, pass it on to the others
+                    this.parent.consumer.notify_one();
+                }
+                return Poll::Ready(());
+            }
+            if unsafe { Pin::new_unchecked(&mut this.wait_c) }
+                .poll(cx)
+                .is_pending()
+            {
+                this.parked = true;
+                return Poll::Pending;
+            }
+        }
+    }
+}
+/// Future of `Cell::set`.
+///
+/// It is not cancel safe: the value is dropped with it, use `into_inner` to get the value back.
+pub struct Set<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    wait_c: C::Listener<'a>,
     val: Option<T>,
 }
-impl<'a, T: Unpin, const MP: usize, const MC: usize> Future for Set<'a, T, MP, MC> {
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Set<'a, T, MP, MC, C, P> {
+    /// Returns the value which is not set yet.
+    pub fn into_inner(self) -> Option<T> {
+        self.val
+    }
+}
+impl<'a, T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Future
+    for Set<'a, T, MP, MC, C, P>
+{
     type Output = Result<(), Error<T>>;
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let val = self.val.take().expect("resumed after completion");
-        match self.parent.try_set(val) {
-            Ok(v) => Poll::Ready(Ok(v)),
-            Err(err) if err.retry => {
-                self.val = Some(err.input);
-                if Pin::new(&mut self.wait_c).poll(cx).is_ready() {
-                    self.poll(cx)
-                } else {
-                    Poll::Pending
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the listeners are never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut val = this.val.take().expect("resumed after completion");
+        loop {
+            match this.parent.try_set(val) {
+                Ok(v) => return Poll::Ready(Ok(v)),
+                Err(err) if err.retry => {
+                    val = err.input;
+                    if unsafe { Pin::new_unchecked(&mut this.wait_c) }
+                        .poll(cx)
+                        .is_pending()
+                    {
+                        this.val = Some(val);
+                        return Poll::Pending;
+                    }
                 }
+                Err(err) => return Poll::Ready(Err(err)),
             }
-            Err(err) => Poll::Ready(Err(err)),
         }
     }
 }
 
-pub struct Take<'a, T, const MP: usize, const MC: usize> {
-    parent: &'a Cell<T, MP, MC>,
-    wait_p: Listener<'a, MC>,
-    wait_c: Listener<'a, MP>,
+/// Future of `Cell::take`.
+///
+/// It is cancel safe, the value is only taken when it is ready.
+pub struct Take<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    wait_p: P::Listener<'a>,
+    wait_c: C::Listener<'a>,
 }
-impl<'a, T: Unpin, const MP: usize, const MC: usize> Future for Take<'a, T, MP, MC> {
+impl<'a, T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Future
+    for Take<'a, T, MP, MC, C, P>
+{
     type Output = Option<T>;
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        match self.parent.try_take() {
-            Ok(v) => Poll::Ready(v),
-            Err(_) => {
-                if Pin::new(&mut self.wait_p).poll(cx).is_ready()
-                    || Pin::new(&mut self.wait_c).poll(cx).is_ready()
-                {
-                    self.poll(cx)
-                } else {
-                    Poll::Pending
-                }
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the listeners are never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        loop {
+            if let Ok(v) = this.parent.try_take() {
+                return Poll::Ready(v);
+            }
+            // register both listeners
+            let p = unsafe { Pin::new_unchecked(&mut this.wait_p) }.poll(cx);
+            let c = unsafe { Pin::new_unchecked(&mut this.wait_c) }.poll(cx);
+            if p.is_pending() && c.is_pending() {
+                return Poll::Pending;
             }
         }
     }
 }
-pub struct Replace<'a, T, const MP: usize, const MC: usize> {
-    parent: &'a Cell<T, MP, MC>,
-    wait_p: Listener<'a, MC>,
-    wait_c: Listener<'a, MP>,
+/// Future of `Cell::replace`.
+///
+/// It is not cancel safe: the value is dropped with it, use `into_inner` to get the value back.
+pub struct Replace<
+    'a,
+    T,
+    const MP: usize,
+    const MC: usize,
+    C: Notifier = Notify<MP>,
+    P: Notifier = Notify<MC>,
+> {
+    parent: &'a Cell<T, MP, MC, C, P>,
+    wait_p: P::Listener<'a>,
+    wait_c: C::Listener<'a>,
     val: Option<T>,
 }
-impl<'a, T: Unpin, const MP: usize, const MC: usize> Future for Replace<'a, T, MP, MC> {
+impl<'a, T, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Replace<'a, T, MP, MC, C, P>
+{
+    /// Returns the value which is not set yet.
+    pub fn into_inner(self) -> Option<T> {
+        self.val
+    }
+}
+impl<'a, T: Unpin, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Future
+    for Replace<'a, T, MP, MC, C, P>
+{
     type Output = Option<T>;
-    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let val = self.val.take().expect("resumed after completion");
-        match self.parent.try_replace(val) {
-            Ok(v) => Poll::Ready(v),
-            Err(err) => {
-                let _ = Pin::new(&mut self.wait_p).poll(cx);
-                let _ = Pin::new(&mut self.wait_c).poll(cx);
-                match self.parent.try_replace(err.input) {
-                    Ok(v) => Poll::Ready(v),
-                    Err(err) => {
-                        self.val = Some(err.input);
-                        Poll::Pending
-                    }
-                }
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the listeners are never moved out
+        let this = unsafe { self.get_unchecked_mut() };
+        let mut val = this.val.take().expect("resumed after completion");
+        loop {
+            match this.parent.try_replace(val) {
+                Ok(v) => return Poll::Ready(v),
+                Err(err) => val = err.input,
+            }
+            // register both listeners
+            let p = unsafe { Pin::new_unchecked(&mut this.wait_p) }.poll(cx);
+            let c = unsafe { Pin::new_unchecked(&mut this.wait_c) }.poll(cx);
+            if p.is_pending() && c.is_pending() {
+                this.val = Some(val);
+                return Poll::Pending;
             }
         }
     }
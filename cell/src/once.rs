@@ -0,0 +1,155 @@
+use crate::atomic::{AtomicU8, Ordering::SeqCst};
+use async_ach_notify::{Notifier, Notify};
+use core::cell::UnsafeCell;
+use core::future::Future;
+use core::mem::MaybeUninit;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_util::future::poll_fn;
+use futures_util::pin_mut;
+
+const EMPTY: u8 = 0;
+const INITIALIZING: u8 = 1;
+const READY: u8 = 2;
+
+/// A cell which can be written to only once.
+///
+/// Only one initializer runs at a time, the others wait for it.
+/// If it fails or is canceled, the next one retries.
+/// `P` is the notify backend of waiters.
+pub struct OnceCell<T, const W: usize, P = Notify<W>> {
+    val: UnsafeCell<MaybeUninit<T>>,
+    state: AtomicU8,
+    waiters: P,
+}
+unsafe impl<T: Send, const W: usize, P: Send> Send for OnceCell<T, W, P> {}
+unsafe impl<T: Send + Sync, const W: usize, P: Sync> Sync for OnceCell<T, W, P> {}
+impl<T, const W: usize, P: Notifier> Default for OnceCell<T, W, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const W: usize, P: Notifier> OnceCell<T, W, P> {
+    pub const fn new() -> Self {
+        Self {
+            val: UnsafeCell::new(MaybeUninit::uninit()),
+            state: AtomicU8::new(EMPTY),
+            waiters: P::NEW,
+        }
+    }
+    pub fn is_initialized(&self) -> bool {
+        self.state.load(SeqCst) == READY
+    }
+    /// Gets the reference to the value.
+    ///
+    /// Returns None if the cell is uninitialized.
+    pub fn get(&self) -> Option<&T> {
+        if self.is_initialized() {
+            Some(unsafe { (*self.val.get()).assume_init_ref() })
+        } else {
+            None
+        }
+    }
+    /// Sets the value of the cell.
+    ///
+    /// Returns Err if the cell is initialized or initializing.
+    pub fn set(&self, val: T) -> Result<(), T> {
+        match self.try_initialize() {
+            Some(guard) => {
+                guard.finish(val);
+                Ok(())
+            }
+            None => Err(val),
+        }
+    }
+    /// Waits until the cell is initialized.
+    pub async fn wait(&self) -> &T {
+        let listener = self.waiters.listen();
+        pin_mut!(listener);
+        self.wait_until(listener, || self.is_initialized()).await;
+        self.get().unwrap()
+    }
+    /// Gets the reference to the value, or initializes it by `fut` if the cell is uninitialized.
+    pub async fn get_or_init<F: Future<Output = T>>(&self, fut: F) -> &T {
+        let ret: Result<&T, core::convert::Infallible> =
+            self.get_or_try_init(async { Ok(fut.await) }).await;
+        match ret {
+            Ok(v) => v,
+            Err(e) => match e {},
+        }
+    }
+    /// Gets the reference to the value, or initializes it by `fut` if the cell is uninitialized.
+    ///
+    /// Returns Err if `fut` fails, then the cell is still uninitialized.
+    pub async fn get_or_try_init<E, F: Future<Output = Result<T, E>>>(
+        &self,
+        fut: F,
+    ) -> Result<&T, E> {
+        let listener = self.waiters.listen();
+        pin_mut!(listener);
+        loop {
+            if let Some(v) = self.get() {
+                return Ok(v);
+            }
+            if let Some(guard) = self.try_initialize() {
+                // dropping the guard resets the cell if `fut` fails or is canceled
+                let val = fut.await?;
+                guard.finish(val);
+                return Ok(self.get().unwrap());
+            }
+            self.wait_until(listener.as_mut(), || {
+                self.state.load(SeqCst) != INITIALIZING
+            })
+            .await;
+        }
+    }
+
+    fn try_initialize(&self) -> Option<Initializing<'_, T, W, P>> {
+        self.state
+            .compare_exchange(EMPTY, INITIALIZING, SeqCst, SeqCst)
+            .ok()
+            .map(|_| Initializing { parent: self })
+    }
+    async fn wait_until(&self, mut listener: Pin<&mut P::Listener<'_>>, done: impl Fn() -> bool) {
+        poll_fn(|cx| {
+            if done() {
+                return Poll::Ready(());
+            }
+            // check again after registering, `notify_all` only wakes the registered waiters
+            let _ = listener.as_mut().poll(cx);
+            if done() {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+impl<T, const W: usize, P> Drop for OnceCell<T, W, P> {
+    fn drop(&mut self) {
+        if *self.state.get_mut() == READY {
+            unsafe { self.val.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// Resets the cell on drop, if it is not finished.
+struct Initializing<'a, T, const W: usize, P: Notifier> {
+    parent: &'a OnceCell<T, W, P>,
+}
+impl<'a, T, const W: usize, P: Notifier> Initializing<'a, T, W, P> {
+    fn finish(self, val: T) {
+        let parent = self.parent;
+        core::mem::forget(self);
+        unsafe { (*parent.val.get()).write(val) };
+        parent.state.store(READY, SeqCst);
+        parent.waiters.notify_all();
+    }
+}
+impl<'a, T, const W: usize, P: Notifier> Drop for Initializing<'a, T, W, P> {
+    fn drop(&mut self) {
+        self.parent.state.store(EMPTY, SeqCst);
+        self.parent.waiters.notify_all();
+    }
+}
@@ -0,0 +1,50 @@
+use async_ach_cell::Cell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Context;
+use futures_test::task;
+
+#[test]
+fn into_inner() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let mut cx = task::noop_context();
+    CELL.try_set(1).unwrap();
+
+    let set = CELL.set(2);
+    assert_eq!(set.into_inner(), Some(2));
+
+    let r = CELL.try_get().unwrap();
+    let mut replace = CELL.replace(3);
+    assert!(Pin::new(&mut replace).poll(&mut cx).is_pending());
+    assert_eq!(replace.into_inner(), Some(3));
+    drop(r);
+    assert_eq!(CELL.try_take().unwrap(), Some(1));
+}
+
+#[test]
+fn pass_on() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let (waker1, count1) = task::new_count_waker();
+    let (waker2, count2) = task::new_count_waker();
+    CELL.try_set(1).unwrap();
+
+    let r = CELL.try_get().unwrap();
+    let mut replace1 = CELL.replace(2);
+    let mut replace2 = CELL.replace(3);
+    assert!(Pin::new(&mut replace1)
+        .poll(&mut Context::from_waker(&waker1))
+        .is_pending());
+    assert!(Pin::new(&mut replace2)
+        .poll(&mut Context::from_waker(&waker2))
+        .is_pending());
+    drop(r);
+    assert_eq!(count1.get() + count2.get(), 1);
+    // the woken one is dropped, the other one is woken
+    if count1.get() == 1 {
+        drop(replace1);
+        assert_eq!(count2.get(), 1);
+    } else {
+        drop(replace2);
+        assert_eq!(count1.get(), 1);
+    }
+}
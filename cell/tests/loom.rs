@@ -0,0 +1,64 @@
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=3 cargo test --test loom --release`.
+//!
+//! The atomics of `ach-cell` are not modeled, its operations are seen as atomic.
+#![cfg(loom)]
+
+use async_ach_cell::Cell;
+use loom::future::block_on;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn update_get() {
+    loom::model(|| {
+        let cell: Arc<Cell<usize, 1, 1>> = Arc::new(Cell::new());
+        cell.try_set(0).unwrap();
+
+        let updater = cell.clone();
+        let th = thread::spawn(move || match updater.try_update(|x| *x += 1) {
+            Ok(()) => true,
+            Err(e) => {
+                assert!(e.retry);
+                false
+            }
+        });
+        match cell.try_get() {
+            Ok(r) => assert!(**r <= 1),
+            Err(e) => assert!(e.retry),
+        }
+        let updated = th.join().unwrap();
+        // the lock is released
+        cell.try_update(|x| *x += 1).ok().unwrap();
+        assert_eq!(**cell.try_get().unwrap(), updated as usize + 1);
+    });
+}
+
+#[test]
+fn replace_after_ref() {
+    loom::model(|| {
+        let cell: Arc<Cell<usize, 1, 1>> = Arc::new(Cell::new());
+        cell.try_set(1).unwrap();
+
+        let r = cell.try_get().unwrap();
+        let replacer = cell.clone();
+        let th = thread::spawn(move || block_on(replacer.replace(2)));
+        drop(r);
+        assert_eq!(th.join().unwrap(), Some(1));
+        assert_eq!(**cell.try_get().unwrap(), 2);
+    });
+}
+
+#[test]
+fn update_wait() {
+    loom::model(|| {
+        let cell: Arc<Cell<usize, 1, 1>> = Arc::new(Cell::new());
+        cell.try_set(0).unwrap();
+
+        let r = cell.try_get().unwrap();
+        let updater = cell.clone();
+        let th = thread::spawn(move || block_on(updater.update(|x| *x += 1)).ok().unwrap());
+        drop(r);
+        th.join().unwrap();
+        assert_eq!(**cell.try_get().unwrap(), 1);
+    });
+}
@@ -0,0 +1,43 @@
+use async_ach_cell::OnceCell;
+use core::future::Future;
+use core::task::Poll;
+use futures_test::future::FutureTestExt;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static CELL: OnceCell<usize, 3> = OnceCell::new();
+    let mut cx = task::noop_context();
+
+    assert_eq!(CELL.get(), None);
+    let mut wait = Box::pin(CELL.wait());
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+
+    // the first initializer fails
+    let mut init1 = Box::pin(CELL.get_or_try_init(async { Err::<usize, ()>(()) }.pending_once()));
+    let mut init2 = Box::pin(CELL.get_or_init(async { 2 }));
+    assert!(init1.as_mut().poll(&mut cx).is_pending());
+    assert!(init2.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(CELL.set(3), Err(3));
+    assert_eq!(init1.as_mut().poll(&mut cx), Poll::Ready(Err(())));
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+
+    // the next one retries
+    assert_eq!(init2.as_mut().poll(&mut cx), Poll::Ready(&2));
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(&2));
+    assert_eq!(CELL.get(), Some(&2));
+    assert_eq!(CELL.set(3), Err(3));
+}
+
+#[test]
+fn cancel() {
+    static CELL: OnceCell<usize, 2> = OnceCell::new();
+    let mut cx = task::noop_context();
+
+    let mut init1 = Box::pin(CELL.get_or_init(async { 1 }.pending_once()));
+    assert!(init1.as_mut().poll(&mut cx).is_pending());
+    let mut init2 = Box::pin(CELL.get_or_init(async { 2 }));
+    assert!(init2.as_mut().poll(&mut cx).is_pending());
+    drop(init1);
+    assert_eq!(init2.as_mut().poll(&mut cx), Poll::Ready(&2));
+}
@@ -0,0 +1,38 @@
+use async_ach_cell::Cell;
+use core::future::Future;
+use core::task::{Context, Poll};
+use futures_test::task;
+
+#[test]
+fn test() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    assert!(!CELL.try_get_mut().is_err_and(|e| e.retry));
+    CELL.try_set(1).unwrap();
+
+    let r = CELL.try_get().unwrap();
+    assert!(CELL.try_get_mut().is_err_and(|e| e.retry));
+    let mut get_mut = Box::pin(CELL.get_mut());
+    assert!(get_mut.as_mut().poll(&mut cx).is_pending());
+    drop(r);
+    let mut w = match get_mut.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => v.unwrap(),
+        Poll::Pending => panic!("not notified"),
+    };
+    *w += 1;
+
+    // other operations wait for the `RefMut`
+    assert!(CELL.try_get().is_err_and(|e| e.retry));
+    assert!(CELL.try_take().is_err_and(|e| e.retry));
+    let mut get = Box::pin(CELL.get());
+    assert!(get.as_mut().poll(&mut cx).is_pending());
+    let woken = count.get();
+    drop(w);
+    assert!(count.get() > woken);
+    match get.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v.unwrap(), 2),
+        Poll::Pending => panic!("not notified"),
+    }
+}
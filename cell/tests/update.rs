@@ -0,0 +1,110 @@
+use async_ach_cell::Cell;
+use core::future::Future;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn update() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let mut cx = task::noop_context();
+
+    assert!(!CELL.try_update(|x| *x += 1).unwrap_err().retry);
+    CELL.try_set(1).unwrap();
+    assert!(CELL.try_update(|x| *x += 1).is_ok());
+    assert_eq!(**CELL.try_get().unwrap(), 2);
+
+    let r = CELL.try_get().unwrap();
+    assert!(CELL.try_update(|x| *x += 1).unwrap_err().retry);
+    let mut update = Box::pin(CELL.update(|x| {
+        *x += 1;
+        *x
+    }));
+    assert!(update.as_mut().poll(&mut cx).is_pending());
+    drop(r);
+    assert!(matches!(update.as_mut().poll(&mut cx), Poll::Ready(Ok(3))));
+}
+
+#[test]
+fn compare_and_swap() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let mut cx = task::noop_context();
+    CELL.try_set(1).unwrap();
+
+    let err = CELL.try_compare_and_swap(&2, 3).unwrap_err();
+    assert!(!err.retry);
+    assert_eq!(err.input, 3);
+    assert_eq!(CELL.try_compare_and_swap(&1, 3).unwrap(), 1);
+
+    let r = CELL.try_get().unwrap();
+    let mut cas = Box::pin(CELL.compare_and_swap(&3, 4));
+    assert!(cas.as_mut().poll(&mut cx).is_pending());
+    drop(r);
+    assert!(matches!(cas.as_mut().poll(&mut cx), Poll::Ready(Ok(3))));
+    assert_eq!(**CELL.try_get().unwrap(), 4);
+}
+
+#[test]
+fn get_or_init() {
+    static CELL: Cell<usize, 4, 4> = Cell::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    let mut init = Box::pin(CELL.get_or_init(|| async {
+        futures_test::future::FutureTestExt::pending_once(async { 1 }).await
+    }));
+    assert!(init.as_mut().poll(&mut cx).is_pending());
+    // not locked by the initializer
+    assert_eq!(CELL.try_take().unwrap(), None);
+    let mut get = Box::pin(CELL.wait_get());
+    assert!(get.as_mut().poll(&mut cx).is_pending());
+    // waits for the running initializer
+    let mut init2 = Box::pin(CELL.get_or_init(|| async { 2 }));
+    assert!(init2.as_mut().poll(&mut cx).is_pending());
+    let woken = count.get();
+    match init.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 1),
+        Poll::Pending => panic!("not initialized"),
+    }
+    assert!(count.get() > woken);
+    drop(init);
+    match get.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 1),
+        Poll::Pending => panic!("not notified"),
+    }
+    match init2.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 1),
+        Poll::Pending => panic!("not notified"),
+    }
+}
+
+#[test]
+fn get_or_init_set() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let mut cx = task::noop_context();
+
+    let mut init = Box::pin(CELL.get_or_init(|| async {
+        futures_test::future::FutureTestExt::pending_once(async { 1 }).await
+    }));
+    assert!(init.as_mut().poll(&mut cx).is_pending());
+    CELL.try_set(2).unwrap();
+    match init.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 2),
+        Poll::Pending => panic!("not initialized"),
+    }
+}
+
+#[test]
+fn get_or_init_cancel() {
+    static CELL: Cell<usize, 4, 4> = Cell::new();
+    let mut cx = task::noop_context();
+
+    let mut init1 = Box::pin(CELL.get_or_init(futures_util::future::pending));
+    assert!(init1.as_mut().poll(&mut cx).is_pending());
+    let mut init2 = Box::pin(CELL.get_or_init(|| async { 2 }));
+    assert!(init2.as_mut().poll(&mut cx).is_pending());
+    drop(init1);
+    match init2.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 2),
+        Poll::Pending => panic!("not notified"),
+    }
+}
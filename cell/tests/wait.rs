@@ -0,0 +1,46 @@
+use async_ach_cell::Cell;
+use core::future::Future;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn wait_get() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let mut cx = task::noop_context();
+
+    let mut get1 = Box::pin(CELL.wait_get());
+    let mut get2 = Box::pin(CELL.wait_get());
+    assert!(get1.as_mut().poll(&mut cx).is_pending());
+    assert!(get2.as_mut().poll(&mut cx).is_pending());
+    CELL.try_set(1).unwrap();
+    match get1.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 1),
+        Poll::Pending => panic!("not notified"),
+    }
+    match get2.as_mut().poll(&mut cx) {
+        Poll::Ready(v) => assert_eq!(**v, 1),
+        Poll::Pending => panic!("not notified"),
+    }
+}
+
+#[test]
+fn wait_empty() {
+    static CELL: Cell<usize, 2, 2> = Cell::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    let mut empty = Box::pin(CELL.wait_empty());
+    assert!(empty.as_mut().poll(&mut cx).is_ready());
+
+    CELL.try_set(1).unwrap();
+    let mut empty1 = Box::pin(CELL.wait_empty());
+    let mut empty2 = Box::pin(CELL.wait_empty());
+    assert!(empty1.as_mut().poll(&mut cx).is_pending());
+    assert!(empty2.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(CELL.try_take().unwrap(), Some(1));
+    assert_eq!(count.get(), 1);
+    assert!(empty1.as_mut().poll(&mut cx).is_ready());
+    // the notice is passed on
+    assert_eq!(count.get(), 2);
+    assert!(empty2.as_mut().poll(&mut cx).is_ready());
+}
@@ -0,0 +1,41 @@
+#![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod mutex;
+mod rwlock;
+
+pub use mutex::*;
+pub use rwlock::*;
+
+use async_ach_waker::pool::{WakerPool, WakerToken};
+use async_ach_waker::WakerEntity;
+use core::task::{Context, Poll};
+
+/// Try to lock, park the task in `pool` if failed.
+fn poll_lock<'a, G, const N: usize>(
+    pool: &'a WakerPool<(), N>,
+    token: &mut Option<WakerToken<'a, (), N>>,
+    cx: &mut Context<'_>,
+    try_lock: impl Fn() -> Option<G>,
+) -> Poll<G> {
+    if let Some(guard) = try_lock() {
+        *token = None;
+        return Poll::Ready(guard);
+    }
+    if token.is_none() {
+        *token = pool.register().ok();
+    }
+    if let Some(token) = token {
+        token.swap(WakerEntity::new(cx.waker().clone(), ()));
+    } else {
+        cx.waker().wake_by_ref();
+    }
+    // the lock may be released before the waker registered
+    if let Some(guard) = try_lock() {
+        *token = None;
+        Poll::Ready(guard)
+    } else {
+        Poll::Pending
+    }
+}
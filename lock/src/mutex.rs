@@ -0,0 +1,160 @@
+use crate::poll_lock;
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+use async_ach_waker::pool::{WakerPool, WakerToken};
+use core::cell::UnsafeCell;
+use core::future::Future;
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use core::task::{Context, Poll};
+
+pub struct MutexGuard<'a, T, const W: usize> {
+    parent: &'a Mutex<T, W>,
+    val: &'a mut T,
+}
+impl<'a, T, const W: usize> Deref for MutexGuard<'a, T, W> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.val
+    }
+}
+impl<'a, T, const W: usize> DerefMut for MutexGuard<'a, T, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.val
+    }
+}
+impl<'a, T, const W: usize> Drop for MutexGuard<'a, T, W> {
+    fn drop(&mut self) {
+        self.parent.unlock();
+    }
+}
+
+/// Async mutex.
+///
+/// `W` is the number of tasks which can wait for the lock at the same time.
+pub struct Mutex<T, const W: usize> {
+    locked: AtomicBool,
+    wakers: WakerPool<(), W>,
+    val: UnsafeCell<T>,
+}
+unsafe impl<T: Send, const W: usize> Send for Mutex<T, W> {}
+unsafe impl<T: Send, const W: usize> Sync for Mutex<T, W> {}
+impl<T, const W: usize> Mutex<T, W> {
+    pub const fn new(val: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            wakers: WakerPool::new(),
+            val: UnsafeCell::new(val),
+        }
+    }
+    pub fn into_inner(self) -> T {
+        self.val.into_inner()
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// No locking is needed, because of the mutable borrow.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.val.get_mut()
+    }
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(SeqCst)
+    }
+    /// Tries to lock the mutex.
+    ///
+    /// Returns None if the mutex is locked.
+    pub fn try_lock(&self) -> Option<MutexGuard<'_, T, W>> {
+        if self
+            .locked
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .is_ok()
+        {
+            Some(MutexGuard {
+                parent: self,
+                val: unsafe { &mut *self.val.get() },
+            })
+        } else {
+            None
+        }
+    }
+    /// Locks the mutex.
+    pub fn lock(&self) -> Lock<'_, T, W> {
+        Lock {
+            parent: self,
+            token: None,
+        }
+    }
+    fn unlock(&self) {
+        self.locked.store(false, SeqCst);
+        self.wakers.wake_one();
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const W: usize> Mutex<T, W> {
+    /// Tries to lock the mutex, and returns a guard which holds the `Arc`.
+    ///
+    /// Returns None if the mutex is locked.
+    pub fn try_lock_owned(self: &Arc<Self>) -> Option<OwnedMutexGuard<T, W>> {
+        let guard = self.try_lock()?;
+        core::mem::forget(guard);
+        Some(OwnedMutexGuard {
+            parent: self.clone(),
+            _val: PhantomData,
+        })
+    }
+    /// Locks the mutex, and returns a guard which holds the `Arc`.
+    pub async fn lock_owned(self: Arc<Self>) -> OwnedMutexGuard<T, W> {
+        core::mem::forget(self.lock().await);
+        OwnedMutexGuard {
+            parent: self,
+            _val: PhantomData,
+        }
+    }
+}
+
+pub struct Lock<'a, T, const W: usize> {
+    parent: &'a Mutex<T, W>,
+    token: Option<WakerToken<'a, (), W>>,
+}
+impl<'a, T, const W: usize> Future for Lock<'a, T, W> {
+    type Output = MutexGuard<'a, T, W>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let parent = self.parent;
+        poll_lock(&parent.wakers, &mut self.token, cx, || parent.try_lock())
+    }
+}
+impl<'a, T, const W: usize> Drop for Lock<'a, T, W> {
+    fn drop(&mut self) {
+        // pass on the wakeup which may have been consumed by this future
+        if self.token.take().is_some() && !self.parent.is_locked() {
+            self.parent.wakers.wake_one();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub struct OwnedMutexGuard<T, const W: usize> {
+    parent: Arc<Mutex<T, W>>,
+    _val: PhantomData<T>,
+}
+#[cfg(feature = "alloc")]
+impl<T, const W: usize> Deref for OwnedMutexGuard<T, W> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.parent.val.get() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const W: usize> DerefMut for OwnedMutexGuard<T, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.parent.val.get() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const W: usize> Drop for OwnedMutexGuard<T, W> {
+    fn drop(&mut self) {
+        self.parent.unlock();
+    }
+}
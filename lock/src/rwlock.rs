@@ -0,0 +1,254 @@
+use crate::poll_lock;
+#[cfg(feature = "alloc")]
+use alloc::sync::Arc;
+use async_ach_waker::pool::{WakerPool, WakerToken};
+use core::cell::UnsafeCell;
+use core::future::Future;
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use core::task::{Context, Poll};
+
+const WRITER: usize = !(usize::MAX >> 1);
+
+pub struct RwLockReadGuard<'a, T, const R: usize, const W: usize> {
+    parent: &'a RwLock<T, R, W>,
+    val: &'a T,
+}
+impl<'a, T, const R: usize, const W: usize> Deref for RwLockReadGuard<'a, T, R, W> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.val
+    }
+}
+impl<'a, T, const R: usize, const W: usize> Drop for RwLockReadGuard<'a, T, R, W> {
+    fn drop(&mut self) {
+        self.parent.unlock_read();
+    }
+}
+
+pub struct RwLockWriteGuard<'a, T, const R: usize, const W: usize> {
+    parent: &'a RwLock<T, R, W>,
+    val: &'a mut T,
+}
+impl<'a, T, const R: usize, const W: usize> Deref for RwLockWriteGuard<'a, T, R, W> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.val
+    }
+}
+impl<'a, T, const R: usize, const W: usize> DerefMut for RwLockWriteGuard<'a, T, R, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.val
+    }
+}
+impl<'a, T, const R: usize, const W: usize> Drop for RwLockWriteGuard<'a, T, R, W> {
+    fn drop(&mut self) {
+        self.parent.unlock_write();
+    }
+}
+
+/// Async reader-writer lock.
+///
+/// `R` is the number of readers and `W` is the number of writers
+/// which can wait for the lock at the same time.
+pub struct RwLock<T, const R: usize, const W: usize> {
+    state: AtomicUsize,
+    readers: WakerPool<(), R>,
+    writers: WakerPool<(), W>,
+    val: UnsafeCell<T>,
+}
+unsafe impl<T: Send, const R: usize, const W: usize> Send for RwLock<T, R, W> {}
+unsafe impl<T: Send + Sync, const R: usize, const W: usize> Sync for RwLock<T, R, W> {}
+impl<T, const R: usize, const W: usize> RwLock<T, R, W> {
+    pub const fn new(val: T) -> Self {
+        Self {
+            state: AtomicUsize::new(0),
+            readers: WakerPool::new(),
+            writers: WakerPool::new(),
+            val: UnsafeCell::new(val),
+        }
+    }
+    pub fn into_inner(self) -> T {
+        self.val.into_inner()
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// No locking is needed, because of the mutable borrow.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.val.get_mut()
+    }
+    /// Tries to lock with shared read access.
+    ///
+    /// Returns None if the lock is held by a writer.
+    pub fn try_read(&self) -> Option<RwLockReadGuard<'_, T, R, W>> {
+        self.state
+            .fetch_update(SeqCst, SeqCst, |x| {
+                if x & WRITER == 0 && x + 1 != WRITER {
+                    Some(x + 1)
+                } else {
+                    None
+                }
+            })
+            .ok()
+            .map(|_| RwLockReadGuard {
+                parent: self,
+                val: unsafe { &*self.val.get() },
+            })
+    }
+    /// Locks with shared read access.
+    pub fn read(&self) -> Read<'_, T, R, W> {
+        Read {
+            parent: self,
+            token: None,
+        }
+    }
+    /// Tries to lock with exclusive write access.
+    ///
+    /// Returns None if the lock is held by a writer or readers.
+    pub fn try_write(&self) -> Option<RwLockWriteGuard<'_, T, R, W>> {
+        self.state
+            .compare_exchange(0, WRITER, SeqCst, SeqCst)
+            .ok()
+            .map(|_| RwLockWriteGuard {
+                parent: self,
+                val: unsafe { &mut *self.val.get() },
+            })
+    }
+    /// Locks with exclusive write access.
+    pub fn write(&self) -> Write<'_, T, R, W> {
+        Write {
+            parent: self,
+            token: None,
+        }
+    }
+    fn unlock_read(&self) {
+        if self.state.fetch_sub(1, SeqCst) == 1 {
+            self.writers.wake_one();
+        }
+    }
+    fn unlock_write(&self) {
+        self.state.fetch_and(!WRITER, SeqCst);
+        self.readers.wake_all();
+        self.writers.wake_one();
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const R: usize, const W: usize> RwLock<T, R, W> {
+    /// Tries to lock with shared read access, and returns a guard which holds the `Arc`.
+    ///
+    /// Returns None if the lock is held by a writer.
+    pub fn try_read_owned(self: &Arc<Self>) -> Option<OwnedRwLockReadGuard<T, R, W>> {
+        let guard = self.try_read()?;
+        core::mem::forget(guard);
+        Some(OwnedRwLockReadGuard {
+            parent: self.clone(),
+            _val: PhantomData,
+        })
+    }
+    /// Locks with shared read access, and returns a guard which holds the `Arc`.
+    pub async fn read_owned(self: Arc<Self>) -> OwnedRwLockReadGuard<T, R, W> {
+        core::mem::forget(self.read().await);
+        OwnedRwLockReadGuard {
+            parent: self,
+            _val: PhantomData,
+        }
+    }
+    /// Tries to lock with exclusive write access, and returns a guard which holds the `Arc`.
+    ///
+    /// Returns None if the lock is held by a writer or readers.
+    pub fn try_write_owned(self: &Arc<Self>) -> Option<OwnedRwLockWriteGuard<T, R, W>> {
+        let guard = self.try_write()?;
+        core::mem::forget(guard);
+        Some(OwnedRwLockWriteGuard {
+            parent: self.clone(),
+            _val: PhantomData,
+        })
+    }
+    /// Locks with exclusive write access, and returns a guard which holds the `Arc`.
+    pub async fn write_owned(self: Arc<Self>) -> OwnedRwLockWriteGuard<T, R, W> {
+        core::mem::forget(self.write().await);
+        OwnedRwLockWriteGuard {
+            parent: self,
+            _val: PhantomData,
+        }
+    }
+}
+
+pub struct Read<'a, T, const R: usize, const W: usize> {
+    parent: &'a RwLock<T, R, W>,
+    token: Option<WakerToken<'a, (), R>>,
+}
+impl<'a, T, const R: usize, const W: usize> Future for Read<'a, T, R, W> {
+    type Output = RwLockReadGuard<'a, T, R, W>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let parent = self.parent;
+        poll_lock(&parent.readers, &mut self.token, cx, || parent.try_read())
+    }
+}
+
+pub struct Write<'a, T, const R: usize, const W: usize> {
+    parent: &'a RwLock<T, R, W>,
+    token: Option<WakerToken<'a, (), W>>,
+}
+impl<'a, T, const R: usize, const W: usize> Future for Write<'a, T, R, W> {
+    type Output = RwLockWriteGuard<'a, T, R, W>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let parent = self.parent;
+        poll_lock(&parent.writers, &mut self.token, cx, || parent.try_write())
+    }
+}
+impl<'a, T, const R: usize, const W: usize> Drop for Write<'a, T, R, W> {
+    fn drop(&mut self) {
+        // pass on the wakeup which may have been consumed by this future
+        if self.token.take().is_some() && self.parent.state.load(SeqCst) == 0 {
+            self.parent.writers.wake_one();
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub struct OwnedRwLockReadGuard<T, const R: usize, const W: usize> {
+    parent: Arc<RwLock<T, R, W>>,
+    _val: PhantomData<T>,
+}
+#[cfg(feature = "alloc")]
+impl<T, const R: usize, const W: usize> Deref for OwnedRwLockReadGuard<T, R, W> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.parent.val.get() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const R: usize, const W: usize> Drop for OwnedRwLockReadGuard<T, R, W> {
+    fn drop(&mut self) {
+        self.parent.unlock_read();
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub struct OwnedRwLockWriteGuard<T, const R: usize, const W: usize> {
+    parent: Arc<RwLock<T, R, W>>,
+    _val: PhantomData<T>,
+}
+#[cfg(feature = "alloc")]
+impl<T, const R: usize, const W: usize> Deref for OwnedRwLockWriteGuard<T, R, W> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.parent.val.get() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const R: usize, const W: usize> DerefMut for OwnedRwLockWriteGuard<T, R, W> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.parent.val.get() }
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T, const R: usize, const W: usize> Drop for OwnedRwLockWriteGuard<T, R, W> {
+    fn drop(&mut self) {
+        self.parent.unlock_write();
+    }
+}
@@ -0,0 +1,48 @@
+use async_ach_lock::Mutex;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static MUTEX: Mutex<usize, 2> = Mutex::new(0);
+    let mut cx = task::noop_context();
+
+    let mut guard1 = MUTEX.try_lock().unwrap();
+    *guard1 += 1;
+    assert!(MUTEX.try_lock().is_none());
+    let mut lock1 = MUTEX.lock();
+    let mut lock2 = MUTEX.lock();
+    assert!(Pin::new(&mut lock1).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut lock2).poll(&mut cx).is_pending());
+    drop(guard1);
+    let mut guard2 = match Pin::new(&mut lock1).poll(&mut cx) {
+        Poll::Ready(guard) => guard,
+        Poll::Pending => panic!(),
+    };
+    assert_eq!(*guard2, 1);
+    *guard2 += 1;
+    assert!(Pin::new(&mut lock2).poll(&mut cx).is_pending());
+    drop(guard2);
+    drop(lock2);
+    assert_eq!(*MUTEX.try_lock().unwrap(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn owned() {
+    use std::sync::Arc;
+    let mutex: Arc<Mutex<usize, 2>> = Arc::new(Mutex::new(0));
+    let mut cx = task::noop_context();
+
+    let mut guard1 = mutex.try_lock_owned().unwrap();
+    *guard1 += 1;
+    let mut lock1 = Box::pin(mutex.clone().lock_owned());
+    assert!(lock1.as_mut().poll(&mut cx).is_pending());
+    drop(guard1);
+    match lock1.as_mut().poll(&mut cx) {
+        Poll::Ready(guard) => assert_eq!(*guard, 1),
+        Poll::Pending => panic!(),
+    };
+}
@@ -0,0 +1,38 @@
+use async_ach_lock::RwLock;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static LOCK: RwLock<usize, 2, 2> = RwLock::new(0);
+    let mut cx = task::noop_context();
+
+    let read1 = LOCK.try_read().unwrap();
+    let read2 = LOCK.try_read().unwrap();
+    assert!(LOCK.try_write().is_none());
+    let mut write1 = LOCK.write();
+    assert!(Pin::new(&mut write1).poll(&mut cx).is_pending());
+    drop(read1);
+    assert!(Pin::new(&mut write1).poll(&mut cx).is_pending());
+    drop(read2);
+    let mut guard = match Pin::new(&mut write1).poll(&mut cx) {
+        Poll::Ready(guard) => guard,
+        Poll::Pending => panic!(),
+    };
+    *guard += 1;
+    let mut read3 = LOCK.read();
+    let mut read4 = LOCK.read();
+    assert!(Pin::new(&mut read3).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut read4).poll(&mut cx).is_pending());
+    drop(guard);
+    match Pin::new(&mut read3).poll(&mut cx) {
+        Poll::Ready(guard) => assert_eq!(*guard, 1),
+        Poll::Pending => panic!(),
+    };
+    match Pin::new(&mut read4).poll(&mut cx) {
+        Poll::Ready(guard) => assert_eq!(*guard, 1),
+        Poll::Pending => panic!(),
+    };
+}
@@ -65,6 +65,33 @@ impl TestMpsc {
             })
         });
     }
+    #[cfg(feature = "alloc")]
+    fn ach_heap_bounded(c: &mut Criterion) {
+        c.bench_function("ach_heap_bounded", |b| {
+            use async_ach_mpmc::heap as mpsc;
+            b.iter(|| {
+                let (tx, rx) = mpsc::bounded::<usize, { Self::SENDER_NUM }, 1>(Self::SENDER_NUM);
+                let ch = Arc::new(tx);
+                thread::scope(|scope| {
+                    for _ in 0..Self::SENDER_NUM {
+                        scope.spawn(|| {
+                            let ch = ch.clone();
+                            futures_executor::block_on(async {
+                                for i in 0..Self::TEST_NUM {
+                                    ch.send(i).await;
+                                }
+                            })
+                        });
+                    }
+                    futures_executor::block_on(async {
+                        for _ in 0..Self::SENDER_NUM * Self::TEST_NUM {
+                            let _ = rx.recv().await;
+                        }
+                    });
+                });
+            })
+        });
+    }
     fn tokio_mpsc(c: &mut Criterion) {
         c.bench_function("tokio_mpsc", |b| {
             b.iter(|| {
@@ -170,6 +197,7 @@ criterion_group!(
     test_mpsc,
     TestMpsc::ach_heapless,
     TestMpsc::ach_heap,
+    TestMpsc::ach_heap_bounded,
     TestMpsc::tokio_mpsc,
     TestMpsc::futures_channel,
     TestMpsc::async_channel,
@@ -1,6 +1,7 @@
 use crate::heapless::Mpmc;
 use ach_util::Error;
 use alloc::sync::Arc;
+use async_ach_ring::heap::Ring;
 
 #[derive(Clone)]
 pub struct Sender<T: Unpin, const N: usize, const MP: usize, const MC: usize> {
@@ -34,3 +35,42 @@ pub fn channel<T: Unpin, const N: usize, const MP: usize, const MC: usize>(
     let rx = tx.clone();
     (Sender { tx }, Receiver { rx })
 }
+
+/// The sending side of a channel created by `bounded`.
+#[derive(Clone)]
+pub struct BoundedSender<T: Unpin, const MP: usize, const MC: usize> {
+    tx: Arc<Ring<T, MP, MC>>,
+}
+impl<T: Unpin, const MP: usize, const MC: usize> BoundedSender<T, MP, MC> {
+    pub fn try_send(&self, val: T) -> Result<(), Error<T>> {
+        self.tx.try_push(val)
+    }
+    pub async fn send(&self, val: T) {
+        self.tx.push(val).await
+    }
+}
+
+/// The receiving side of a channel created by `bounded`.
+#[derive(Clone)]
+pub struct BoundedReceiver<T: Unpin, const MP: usize, const MC: usize> {
+    rx: Arc<Ring<T, MP, MC>>,
+}
+impl<T: Unpin, const MP: usize, const MC: usize> BoundedReceiver<T, MP, MC> {
+    pub fn try_recv(&self) -> Result<T, Error<()>> {
+        self.rx.try_pop()
+    }
+    pub async fn recv(&self) -> T {
+        self.rx.pop().await
+    }
+}
+
+/// Creates a channel with the capacity chosen at runtime.
+///
+/// Panics if `capacity` is 0.
+pub fn bounded<T: Unpin, const MP: usize, const MC: usize>(
+    capacity: usize,
+) -> (BoundedSender<T, MP, MC>, BoundedReceiver<T, MP, MC>) {
+    let tx = Arc::new(Ring::with_capacity(capacity));
+    let rx = tx.clone();
+    (BoundedSender { tx }, BoundedReceiver { rx })
+}
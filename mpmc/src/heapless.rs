@@ -1,19 +1,31 @@
 use ach_util::Error;
+use async_ach_notify::{Notifier, Notify};
 use async_ach_ring::Ring;
 use core::ops::Deref;
 
-pub struct Sender<'a, T, const N: usize, const MP: usize, const MC: usize> {
-    mpmc: &'a Mpmc<T, N, MP, MC>,
+pub struct Sender<
+    'a,
+    T,
+    const N: usize,
+    const MP: usize,
+    const MC: usize,
+    C = Notify<MP>,
+    P = Notify<MC>,
+> {
+    mpmc: &'a Mpmc<T, N, MP, MC, C, P>,
 }
-impl<'a, T, const N: usize, const MP: usize, const MC: usize> Sender<'a, T, N, MP, MC> {
-    const fn new(mpmc: &'a Mpmc<T, N, MP, MC>) -> Self {
+impl<'a, T, const N: usize, const MP: usize, const MC: usize, C, P> Sender<'a, T, N, MP, MC, C, P> {
+    const fn new(mpmc: &'a Mpmc<T, N, MP, MC, C, P>) -> Self {
         Sender { mpmc }
     }
 }
-impl<'a, T: Unpin, const N: usize, const MP: usize, const MC: usize> Sender<'a, T, N, MP, MC> {
+impl<'a, T: Unpin, const N: usize, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Sender<'a, T, N, MP, MC, C, P>
+{
     /// Appends an element to the back of the Ring.
     ///
-    /// Returns Err if the Ring is full or in critical section.
+    /// Returns Err if the Ring is full.
+    /// In overwrite mode, the oldest element is dropped if full, see `force_send`.
     pub fn try_send(&self, t: T) -> Result<(), Error<T>> {
         self.mpmc.try_push(t)
     }
@@ -21,20 +33,38 @@ impl<'a, T: Unpin, const N: usize, const MP: usize, const MC: usize> Sender<'a,
     pub async fn send(&self, t: T) {
         self.mpmc.push(t).await
     }
+    /// Appends an element to the back of the Ring, evicts the oldest element and returns it if full.
+    ///
+    /// Returns Err with `retry` if the first element is peeked, or is being written by another sender.
+    pub fn force_send(&self, t: T) -> Result<Option<T>, Error<T>> {
+        self.mpmc.force_push(t)
+    }
 }
 
-pub struct Receiver<'a, T, const N: usize, const MP: usize, const MC: usize> {
-    mpmc: &'a Mpmc<T, N, MP, MC>,
+pub struct Receiver<
+    'a,
+    T,
+    const N: usize,
+    const MP: usize,
+    const MC: usize,
+    C = Notify<MP>,
+    P = Notify<MC>,
+> {
+    mpmc: &'a Mpmc<T, N, MP, MC, C, P>,
 }
-impl<'a, T, const N: usize, const MP: usize, const MC: usize> Receiver<'a, T, N, MP, MC> {
-    const fn new(mpmc: &'a Mpmc<T, N, MP, MC>) -> Self {
+impl<'a, T, const N: usize, const MP: usize, const MC: usize, C, P>
+    Receiver<'a, T, N, MP, MC, C, P>
+{
+    const fn new(mpmc: &'a Mpmc<T, N, MP, MC, C, P>) -> Self {
         Receiver { mpmc }
     }
 }
-impl<'a, T: Unpin, const N: usize, const MP: usize, const MC: usize> Receiver<'a, T, N, MP, MC> {
+impl<'a, T: Unpin, const N: usize, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Receiver<'a, T, N, MP, MC, C, P>
+{
     /// Removes the first element and returns it.
     ///
-    /// Returns Err if the Ring is empty or in critical section.
+    /// Returns Err if the Ring is empty, or the first element is peeked.
     pub fn try_recv(&self) -> Result<T, Error<()>> {
         self.mpmc.try_pop()
     }
@@ -42,24 +72,54 @@ impl<'a, T: Unpin, const N: usize, const MP: usize, const MC: usize> Receiver<'a
     pub async fn recv(&self) -> T {
         self.mpmc.pop().await
     }
+    /// Removes the first element and returns it.
+    ///
+    /// If several receivers are parked, the one with the highest `priority` is woken first,
+    /// when the producer notify `P` is a `PriorityNotify`. Otherwise the priority is ignored.
+    pub async fn recv_with_priority(&self, priority: usize) -> T {
+        self.mpmc.pop_with_priority(priority).await
+    }
+    /// Returns the number of evicted elements, and resets it.
+    pub fn take_overflowed(&self) -> usize {
+        self.mpmc.take_overflowed()
+    }
 }
 
-pub struct Mpmc<T, const N: usize, const MP: usize, const MC: usize> {
-    ring: Ring<T, N, MP, MC>,
+/// `C` and `P` are the notify backends of consumers and producers, see `Ring`.
+pub struct Mpmc<T, const N: usize, const MP: usize, const MC: usize, C = Notify<MP>, P = Notify<MC>>
+{
+    ring: Ring<T, N, MP, MC, C, P>,
+}
+impl<T, const N: usize, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Default
+    for Mpmc<T, N, MP, MC, C, P>
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
-impl<T, const N: usize, const MP: usize, const MC: usize> Mpmc<T, N, MP, MC> {
+impl<T, const N: usize, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Mpmc<T, N, MP, MC, C, P>
+{
     pub const fn new() -> Self {
         Self { ring: Ring::new() }
     }
-    pub const fn sender(&self) -> Sender<T, N, MP, MC> {
+    /// Creates a Mpmc in which sending evicts the oldest element if full, see `Ring::new_overwrite`.
+    ///
+    /// `force_send` returns the evicted element, `try_send` and `send` drop it.
+    pub const fn new_overwrite() -> Self {
+        Self {
+            ring: Ring::new_overwrite(),
+        }
+    }
+    pub const fn sender(&self) -> Sender<'_, T, N, MP, MC, C, P> {
         Sender::new(self)
     }
-    pub const fn recver(&self) -> Receiver<T, N, MP, MC> {
+    pub const fn recver(&self) -> Receiver<'_, T, N, MP, MC, C, P> {
         Receiver::new(self)
     }
 }
-impl<T, const N: usize, const MP: usize, const MC: usize> Deref for Mpmc<T, N, MP, MC> {
-    type Target = Ring<T, N, MP, MC>;
+impl<T, const N: usize, const MP: usize, const MC: usize, C, P> Deref for Mpmc<T, N, MP, MC, C, P> {
+    type Target = Ring<T, N, MP, MC, C, P>;
     fn deref(&self) -> &Self::Target {
         &self.ring
     }
@@ -18,9 +18,8 @@ impl<T, const N: usize, const MP: usize, const MC: usize> Ring<T, N, MP, MC> {
 }
 impl<T: Unpin, const N: usize, const MP: usize, const MC: usize> Ring<T, N, MP, MC> {
     pub fn try_push(&self, val: T) -> Result<(), Error<T>> {
-        self.buf.push(val).map(|x| {
+        self.buf.push(val).inspect(|_| {
             self.producer.notify(1);
-            x
         })
     }
     pub async fn push(&self, mut val: T) {
@@ -37,9 +36,8 @@ impl<T: Unpin, const N: usize, const MP: usize, const MC: usize> Ring<T, N, MP,
     }
 
     pub fn try_pop(&self) -> Result<T, Error<()>> {
-        self.buf.pop().map(|x| {
+        self.buf.pop().inspect(|_| {
             self.consumer.notify(1);
-            x
         })
     }
     pub async fn pop(&self) -> T {
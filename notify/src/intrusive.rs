@@ -0,0 +1,97 @@
+use crate::atomic::{AtomicUsize, Ordering::SeqCst};
+use async_ach_waker::sync_list::{Registration, SyncWakerList};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_util::Stream;
+
+/// Same as `Notify`, but the number of waiters is unbounded.
+///
+/// The waker slot is stored in the `IntrusiveListener` future,
+/// so the listener must be pinned before polling.
+pub struct IntrusiveNotify {
+    permit: AtomicUsize,
+    wakers: SyncWakerList<()>,
+}
+impl Default for IntrusiveNotify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl IntrusiveNotify {
+    pub const fn new() -> Self {
+        Self {
+            permit: AtomicUsize::new(0),
+            wakers: SyncWakerList::new(),
+        }
+    }
+    /// Notify a waiter
+    pub fn notify_one(&self) {
+        self.permit.fetch_add(1, SeqCst);
+        self.wakers.wake_one();
+    }
+    pub fn notify_waiters(&self) -> usize {
+        let mut num = 0;
+        loop {
+            self.permit.fetch_add(1, SeqCst);
+            if !self.wakers.wake_one() {
+                self.get_permit();
+                break;
+            } else {
+                num += 1;
+            }
+        }
+        num
+    }
+    /// Had been notified
+    pub fn had_notified(&self) -> bool {
+        self.permit.load(SeqCst) != 0
+    }
+    /// Wait for a notice
+    pub fn listen(&self) -> IntrusiveListener<'_> {
+        IntrusiveListener {
+            parent: self,
+            registration: self.wakers.registration(()),
+        }
+    }
+    fn get_permit(&self) -> bool {
+        self.permit
+            .fetch_update(SeqCst, SeqCst, |x| if x > 0 { Some(x - 1) } else { None })
+            .is_ok()
+    }
+}
+
+pub struct IntrusiveListener<'a> {
+    parent: &'a IntrusiveNotify,
+    registration: Registration<'a, ()>,
+}
+impl<'a> Drop for IntrusiveListener<'a> {
+    fn drop(&mut self) {
+        // the notice may be sent to this listener, pass it on to the others
+        if self.registration.is_waked() && self.parent.had_notified() {
+            self.parent.wakers.wake_one();
+        }
+    }
+}
+impl<'a> Stream for IntrusiveListener<'a> {
+    type Item = ();
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let parent = self.parent;
+        // Safety: the registration is pinned with the listener.
+        unsafe { self.map_unchecked_mut(|x| &mut x.registration) }.register(cx.waker());
+        if parent.get_permit() {
+            Poll::Ready(Some(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+impl<'a> Future for IntrusiveListener<'a> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(_) => Poll::Ready(()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
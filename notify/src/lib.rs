@@ -1,10 +1,60 @@
+//! Wait for wake.
+//!
+//! # Interrupt safety
+//!
+//! The following methods never block or allocate, so they can be called from an ISR.
+//!
+//! Wait-free, a bounded number of loads and stores:
+//! - `Notify::had_notified`
+//! - `Ring::len`
+//!
+//! Lock-free, they retry a CAS loop while another context changes the same state word,
+//! but never wait for another context to finish:
+//! - `Notify::notify_one`, `Notify::notify_waiters`, `PriorityNotify::notify_one`
+//! - `WakerPool::wake_one`, `WakerPool::wake_highest`, `WakerPool::wake_all`
+//! - `Ring::try_push`, `Ring::force_push`, `Ring::try_pop`, `Ring::peek_with`
+//! - `Spsc` `Sender::try_send`, `Sender::force_send`, `Receiver::try_recv`
+//!
+//! They call `Waker::wake`, so the wakers of the executor must be interrupt safe too.
+//! `IntrusiveNotify` is not in the subset, its list is guarded by a spin lock.
+//!
+//! # Targets without CAS
+//!
+//! With the `critical-section` feature, the state words of `Notify`, `WakerPool`, `Ring` and `Spsc`
+//! are `atomic-polyfill` atomics, which emulate CAS in a critical section.
+//! `IntrusiveNotify` and the waker lists need pointer CAS, so they are only built on targets with it.
+//! Run `cargo make check-embedded` to build for thumbv6m.
+
 #![no_std]
 
+#[cfg(loom)]
+#[doc(hidden)]
+pub use async_ach_waker::loom_atomic;
+
+#[cfg(loom)]
+use async_ach_waker::loom_atomic as atomic;
+#[cfg(all(not(loom), feature = "critical-section"))]
+use atomic_polyfill as atomic;
+#[cfg(all(not(loom), not(feature = "critical-section")))]
+use core::sync::atomic;
+
+#[cfg(target_has_atomic = "ptr")]
+mod intrusive;
+mod notifier;
+mod priority;
+mod tagged;
+
+#[cfg(target_has_atomic = "ptr")]
+pub use intrusive::{IntrusiveListener, IntrusiveNotify};
+pub use notifier::Notifier;
+pub use priority::{PriorityListener, PriorityNotify};
+pub use tagged::{TaggedListener, TaggedNotify};
+
 use async_ach_waker::pool::{WakerPool, WakerToken};
 use async_ach_waker::WakerEntity;
+use atomic::{AtomicUsize, Ordering::SeqCst};
 use core::future::Future;
 use core::pin::Pin;
-use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use core::task::{Context, Poll};
 use futures_util::Stream;
 
@@ -12,6 +62,11 @@ pub struct Notify<const W: usize> {
     permit: AtomicUsize,
     wakers: WakerPool<(), W>,
 }
+impl<const W: usize> Default for Notify<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl<const W: usize> Notify<W> {
     pub const fn new() -> Self {
         Self {
@@ -61,7 +116,7 @@ pub struct Listener<'a, const W: usize> {
 }
 impl<'a, const W: usize> Listener<'a, W> {
     pub fn pendable(&mut self) -> bool {
-        if let Some(_) = &self.token {
+        if self.token.is_some() {
             true
         } else if let Ok(token) = self.parent.wakers.register() {
             self.token = Some(token);
@@ -71,6 +126,16 @@ impl<'a, const W: usize> Listener<'a, W> {
         }
     }
 }
+impl<'a, const W: usize> Drop for Listener<'a, W> {
+    fn drop(&mut self) {
+        // the notice may be sent to this listener, pass it on to the others
+        if let Some(token) = &self.token {
+            if token.is_waked() && self.parent.had_notified() {
+                self.parent.wakers.wake_one();
+            }
+        }
+    }
+}
 impl<'a, const W: usize> Stream for Listener<'a, W> {
     type Item = ();
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
@@ -0,0 +1,83 @@
+#[cfg(target_has_atomic = "ptr")]
+use crate::{IntrusiveListener, IntrusiveNotify};
+use crate::{Listener, Notify, PriorityListener, PriorityNotify};
+use core::future::Future;
+use futures_util::Stream;
+
+/// The notify backend of channels.
+///
+/// It is implemented by `Notify`, `PriorityNotify` and `IntrusiveNotify`,
+/// other crates can implement it to supply their own wakeup strategy,
+/// e.g. waking from an interrupt.
+///
+/// A notice must not be lost: if `notify_one` is called before a listener is polled,
+/// the next poll of a listener returns Ready.
+pub trait Notifier {
+    /// The initial value, used by `const fn new` of channels.
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NEW: Self;
+    type Listener<'a>: Future<Output = ()> + Stream<Item = ()>
+    where
+        Self: 'a;
+    /// Wait for a notice
+    fn listen(&self) -> Self::Listener<'_>;
+    /// Wait for a notice, the listener with a higher `priority` should be notified first.
+    ///
+    /// The priority is ignored by default, only `PriorityNotify` orders its listeners by it.
+    fn listen_with_priority(&self, priority: usize) -> Self::Listener<'_> {
+        let _ = priority;
+        self.listen()
+    }
+    /// Notify a waiter
+    fn notify_one(&self);
+    /// Notify all registered waiters
+    ///
+    /// returns the number of had notified
+    fn notify_all(&self) -> usize;
+}
+
+impl<const W: usize> Notifier for Notify<W> {
+    const NEW: Self = Notify::new();
+    type Listener<'a> = Listener<'a, W>;
+    fn listen(&self) -> Self::Listener<'_> {
+        Notify::listen(self)
+    }
+    fn notify_one(&self) {
+        Notify::notify_one(self)
+    }
+    fn notify_all(&self) -> usize {
+        Notify::notify_waiters(self)
+    }
+}
+
+impl<const W: usize> Notifier for PriorityNotify<W> {
+    const NEW: Self = PriorityNotify::new();
+    type Listener<'a> = PriorityListener<'a, W>;
+    fn listen(&self) -> Self::Listener<'_> {
+        PriorityNotify::listen(self)
+    }
+    fn listen_with_priority(&self, priority: usize) -> Self::Listener<'_> {
+        PriorityNotify::listen_with_priority(self, priority)
+    }
+    fn notify_one(&self) {
+        PriorityNotify::notify_one(self)
+    }
+    fn notify_all(&self) -> usize {
+        PriorityNotify::notify_waiters(self)
+    }
+}
+
+#[cfg(target_has_atomic = "ptr")]
+impl Notifier for IntrusiveNotify {
+    const NEW: Self = IntrusiveNotify::new();
+    type Listener<'a> = IntrusiveListener<'a>;
+    fn listen(&self) -> Self::Listener<'_> {
+        IntrusiveNotify::listen(self)
+    }
+    fn notify_one(&self) {
+        IntrusiveNotify::notify_one(self)
+    }
+    fn notify_all(&self) -> usize {
+        IntrusiveNotify::notify_waiters(self)
+    }
+}
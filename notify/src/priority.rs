@@ -0,0 +1,126 @@
+use crate::atomic::{AtomicUsize, Ordering::SeqCst};
+use async_ach_waker::pool::{WakerPool, WakerToken};
+use async_ach_waker::WakerEntity;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_util::Stream;
+
+/// Same as `Notify`, but `notify_one` wakes the parked listener with the highest priority.
+///
+/// Each `notify_one` scans all `W` places of the pool, so use `Notify` if the order does not matter.
+pub struct PriorityNotify<const W: usize> {
+    permit: AtomicUsize,
+    wakers: WakerPool<usize, W>,
+}
+impl<const W: usize> Default for PriorityNotify<W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const W: usize> PriorityNotify<W> {
+    pub const fn new() -> Self {
+        Self {
+            permit: AtomicUsize::new(0),
+            wakers: WakerPool::new(),
+        }
+    }
+    /// Notify the waiter with the highest priority
+    pub fn notify_one(&self) {
+        self.permit.fetch_add(1, SeqCst);
+        self.wakers.wake_highest();
+    }
+    pub fn notify_waiters(&self) -> usize {
+        let mut num = 0;
+        loop {
+            self.permit.fetch_add(1, SeqCst);
+            if !self.wakers.wake_one() {
+                self.get_permit();
+                break;
+            } else {
+                num += 1;
+            }
+        }
+        num
+    }
+    /// Had been notified
+    pub fn had_notified(&self) -> bool {
+        self.permit.load(SeqCst) != 0
+    }
+    /// Wait for a notice with the lowest priority
+    pub fn listen(&self) -> PriorityListener<'_, W> {
+        self.listen_with_priority(0)
+    }
+    /// Wait for a notice, the parked listener with the highest `priority` is notified first.
+    pub fn listen_with_priority(&self, priority: usize) -> PriorityListener<'_, W> {
+        PriorityListener {
+            parent: self,
+            priority,
+            token: None,
+        }
+    }
+    fn get_permit(&self) -> bool {
+        self.permit
+            .fetch_update(SeqCst, SeqCst, |x| if x > 0 { Some(x - 1) } else { None })
+            .is_ok()
+    }
+}
+
+pub struct PriorityListener<'a, const W: usize> {
+    parent: &'a PriorityNotify<W>,
+    priority: usize,
+    token: Option<WakerToken<'a, usize, W>>,
+}
+impl<'a, const W: usize> PriorityListener<'a, W> {
+    pub fn priority(&self) -> usize {
+        self.priority
+    }
+    pub fn pendable(&mut self) -> bool {
+        if self.token.is_some() {
+            true
+        } else if let Ok(token) = self.parent.wakers.register() {
+            self.token = Some(token);
+            true
+        } else {
+            false
+        }
+    }
+}
+impl<'a, const W: usize> Drop for PriorityListener<'a, W> {
+    fn drop(&mut self) {
+        // the notice may be sent to this listener, pass it on to the next highest
+        if let Some(token) = &self.token {
+            if token.is_waked() && self.parent.had_notified() {
+                self.parent.wakers.wake_highest();
+            }
+        }
+    }
+}
+impl<'a, const W: usize> Stream for PriorityListener<'a, W> {
+    type Item = ();
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let waker = cx.waker();
+        if self.pendable() {
+            self.token
+                .as_ref()
+                .unwrap()
+                .swap(WakerEntity::new(waker.clone(), self.priority));
+        } else {
+            waker.wake_by_ref();
+        }
+        if self.parent.get_permit() {
+            Poll::Ready(Some(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+impl<'a, const W: usize> Future for PriorityListener<'a, W> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(_) => Poll::Ready(()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
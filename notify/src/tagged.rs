@@ -0,0 +1,115 @@
+use crate::atomic::{AtomicBool, Ordering::SeqCst};
+use async_ach_waker::pool::{WakerPool, WakerToken};
+use async_ach_waker::WakerEntity;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_util::Stream;
+
+/// Same as `Notify`, but each listener is registered with a key,
+/// and only the listeners whose key matches are notified.
+///
+/// Notices are not counted: a listener receives the notices sent after its first poll,
+/// and several notices before its next poll are received once.
+pub struct TaggedNotify<K, const W: usize> {
+    wakers: WakerPool<K, W>,
+    /// set before waking the listener of the same index in `wakers`
+    notified: [AtomicBool; W],
+}
+impl<K, const W: usize> Default for TaggedNotify<K, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<K, const W: usize> TaggedNotify<K, W> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const FALSE: AtomicBool = AtomicBool::new(false);
+    pub const fn new() -> Self {
+        Self {
+            wakers: WakerPool::new(),
+            notified: [Self::FALSE; W],
+        }
+    }
+    /// Notify all listeners whose key matches the predicate
+    ///
+    /// returns the number of had notified
+    pub fn notify_matching(&self, mut f: impl FnMut(&K) -> bool) -> usize {
+        self.wakers.wake_if(|i, k| {
+            if f(k) {
+                self.notified[i].store(true, SeqCst);
+                true
+            } else {
+                false
+            }
+        })
+    }
+    /// Notify all listeners
+    pub fn notify_all(&self) -> usize {
+        self.notify_matching(|_| true)
+    }
+    /// Wait for a notice to `key`
+    pub fn listen(&self, key: K) -> TaggedListener<'_, K, W> {
+        TaggedListener {
+            parent: self,
+            key,
+            token: None,
+        }
+    }
+}
+impl<K: PartialEq, const W: usize> TaggedNotify<K, W> {
+    /// Notify all listeners of `key`
+    pub fn notify_key(&self, key: &K) -> usize {
+        self.notify_matching(|k| k == key)
+    }
+}
+
+/// If there is no free slot in the `TaggedNotify`, it wakes itself to register again like `Listener`,
+/// the notices sent before it is registered are not received.
+pub struct TaggedListener<'a, K, const W: usize> {
+    parent: &'a TaggedNotify<K, W>,
+    key: K,
+    token: Option<WakerToken<'a, K, W>>,
+}
+impl<'a, K, const W: usize> TaggedListener<'a, K, W> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+impl<'a, K: Clone + Unpin, const W: usize> Stream for TaggedListener<'a, K, W> {
+    type Item = ();
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let parent = self.parent;
+        let token = match &self.token {
+            Some(token) => token,
+            None => {
+                if let Ok(token) = parent.wakers.register() {
+                    parent.notified[token.index()].store(false, SeqCst);
+                    token.swap(WakerEntity::new(cx.waker().clone(), self.key.clone()));
+                    self.token = Some(token);
+                } else {
+                    cx.waker().wake_by_ref();
+                }
+                return Poll::Pending;
+            }
+        };
+        // the key is kept, so `notify_matching` never skips this listener
+        if !token.set_waker(cx.waker()) {
+            token.swap(WakerEntity::new(cx.waker().clone(), self.key.clone()));
+        }
+        // the flag is set before the waker is taken, so check it after setting the waker
+        if parent.notified[token.index()].swap(false, SeqCst) {
+            Poll::Ready(Some(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+impl<'a, K: Clone + Unpin, const W: usize> Future for TaggedListener<'a, K, W> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.poll_next(cx) {
+            Poll::Ready(_) => Poll::Ready(()),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use async_ach_notify::{IntrusiveNotify, Notify};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Context;
+use futures_test::task;
+
+#[test]
+fn notify() {
+    static NOTIFY: Notify<2> = Notify::new();
+    let (waker1, count1) = task::new_count_waker();
+    let (waker2, count2) = task::new_count_waker();
+
+    let mut listener1 = NOTIFY.listen();
+    let mut listener2 = NOTIFY.listen();
+    assert!(Pin::new(&mut listener1)
+        .poll(&mut Context::from_waker(&waker1))
+        .is_pending());
+    assert!(Pin::new(&mut listener2)
+        .poll(&mut Context::from_waker(&waker2))
+        .is_pending());
+    NOTIFY.notify_one();
+    assert_eq!(count1.get() + count2.get(), 1);
+    // drop the woken one
+    if count1.get() == 1 {
+        drop(listener1);
+        assert_eq!(count2.get(), 1);
+    } else {
+        drop(listener2);
+        assert_eq!(count1.get(), 1);
+    }
+}
+
+#[test]
+fn intrusive() {
+    static NOTIFY: IntrusiveNotify = IntrusiveNotify::new();
+    let (waker1, count1) = task::new_count_waker();
+    let (waker2, count2) = task::new_count_waker();
+
+    let mut listener1 = Box::pin(NOTIFY.listen());
+    let mut listener2 = Box::pin(NOTIFY.listen());
+    assert!(listener1
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker1))
+        .is_pending());
+    assert!(listener2
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker2))
+        .is_pending());
+    NOTIFY.notify_one();
+    assert_eq!(count1.get() + count2.get(), 1);
+    if count1.get() == 1 {
+        drop(listener1);
+        assert_eq!(count2.get(), 1);
+    } else {
+        drop(listener2);
+        assert_eq!(count1.get(), 1);
+    }
+}
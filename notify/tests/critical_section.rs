@@ -0,0 +1,27 @@
+#![cfg(feature = "critical-section")]
+
+use async_ach_notify::Notify;
+use core::future::Future;
+use core::pin::Pin;
+use futures_test::task;
+use std::thread;
+
+#[test]
+fn test() {
+    static NOTIFY: Notify<2> = Notify::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    let mut listener = NOTIFY.listen();
+    assert!(Pin::new(&mut listener).poll(&mut cx).is_pending());
+    // notify from an "interrupt"
+    thread::spawn(|| critical_section::with(|_| NOTIFY.notify_one()))
+        .join()
+        .unwrap();
+    assert_eq!(count.get(), 1);
+    assert!(NOTIFY.had_notified());
+    assert!(Pin::new(&mut listener).poll(&mut cx).is_ready());
+    assert!(Pin::new(&mut listener).poll(&mut cx).is_pending());
+    critical_section::with(|_| assert_eq!(NOTIFY.notify_waiters(), 1));
+    assert!(Pin::new(&mut listener).poll(&mut cx).is_ready());
+}
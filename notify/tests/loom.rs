@@ -0,0 +1,55 @@
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=3 cargo test --test loom --release`.
+#![cfg(loom)]
+
+use async_ach_notify::Notify;
+use loom::future::block_on;
+use loom::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn notify_one() {
+    loom::model(|| {
+        let notify: Arc<Notify<1>> = Arc::new(Notify::new());
+
+        let notifier = notify.clone();
+        let th = thread::spawn(move || notifier.notify_one());
+        block_on(notify.listen());
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn notify_waiters() {
+    loom::model(|| {
+        let notify: Arc<Notify<2>> = Arc::new(Notify::new());
+        let done = Arc::new(AtomicBool::new(false));
+
+        let listener = notify.clone();
+        let listened = done.clone();
+        let th = thread::spawn(move || {
+            block_on(listener.listen());
+            listened.store(true, SeqCst);
+        });
+        // the listener may take the permit of a `notify_waiters` before it is registered
+        while !done.load(SeqCst) {
+            notify.notify_waiters();
+            thread::yield_now();
+        }
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn notify_one_by_one() {
+    loom::model(|| {
+        let notify: Arc<Notify<2>> = Arc::new(Notify::new());
+
+        let listener = notify.clone();
+        let th = thread::spawn(move || block_on(listener.listen()));
+        notify.notify_one();
+        notify.notify_one();
+        block_on(notify.listen());
+        th.join().unwrap();
+    });
+}
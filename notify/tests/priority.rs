@@ -0,0 +1,36 @@
+use async_ach_notify::PriorityNotify;
+use core::future::Future;
+use core::pin::Pin;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static NOTIFY: PriorityNotify<3> = PriorityNotify::new();
+    let mut cx = task::noop_context();
+
+    let mut low = NOTIFY.listen_with_priority(1);
+    let mut high = NOTIFY.listen_with_priority(3);
+    let mut mid = NOTIFY.listen_with_priority(2);
+    assert!(Pin::new(&mut low).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut high).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut mid).poll(&mut cx).is_pending());
+
+    let (waker, count) = task::new_count_waker();
+    let mut counted = core::task::Context::from_waker(&waker);
+    assert!(Pin::new(&mut high).poll(&mut counted).is_pending());
+    NOTIFY.notify_one();
+    assert_eq!(count.get(), 1);
+    assert!(Pin::new(&mut high).poll(&mut cx).is_ready());
+    drop(high);
+
+    assert!(Pin::new(&mut mid).poll(&mut counted).is_pending());
+    NOTIFY.notify_one();
+    assert_eq!(count.get(), 2);
+    assert!(Pin::new(&mut mid).poll(&mut cx).is_ready());
+    drop(mid);
+
+    assert!(Pin::new(&mut low).poll(&mut counted).is_pending());
+    NOTIFY.notify_one();
+    assert_eq!(count.get(), 3);
+    assert!(Pin::new(&mut low).poll(&mut cx).is_ready());
+}
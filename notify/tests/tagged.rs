@@ -0,0 +1,82 @@
+use async_ach_notify::TaggedNotify;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Context;
+use core::time::Duration;
+use futures_executor::block_on;
+use futures_test::task;
+use futures_util::StreamExt;
+use std::sync::{mpsc, Arc, Barrier};
+use std::thread;
+
+#[test]
+fn test() {
+    static NOTIFY: TaggedNotify<u8, 3> = TaggedNotify::new();
+    let mut cx = task::noop_context();
+
+    let mut listener1 = NOTIFY.listen(1);
+    let mut listener2 = NOTIFY.listen(2);
+    let mut listener3 = NOTIFY.listen(3);
+    assert_eq!(NOTIFY.notify_key(&1), 0);
+    assert!(Pin::new(&mut listener1).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut listener2).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut listener3).poll(&mut cx).is_pending());
+
+    assert_eq!(NOTIFY.notify_key(&1), 1);
+    assert_eq!(NOTIFY.notify_key(&1), 0);
+    assert!(Pin::new(&mut listener1).poll(&mut cx).is_ready());
+    assert!(Pin::new(&mut listener2).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut listener3).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut listener1).poll(&mut cx).is_pending());
+
+    assert_eq!(NOTIFY.notify_matching(|k| *k >= 2), 2);
+    assert!(Pin::new(&mut listener1).poll(&mut cx).is_pending());
+    assert!(Pin::new(&mut listener2).poll(&mut cx).is_ready());
+    assert!(Pin::new(&mut listener3).poll(&mut cx).is_ready());
+
+    drop(listener3);
+    assert_eq!(NOTIFY.notify_all(), 2);
+    assert!(Pin::new(&mut listener1).poll(&mut cx).is_ready());
+    assert!(Pin::new(&mut listener2).poll(&mut cx).is_ready());
+
+    // no free slot
+    let mut listener3 = NOTIFY.listen(3);
+    let mut listener4 = NOTIFY.listen(4);
+    assert!(Pin::new(&mut listener3).poll(&mut cx).is_pending());
+    let (waker, count) = task::new_count_waker();
+    let mut cx4 = Context::from_waker(&waker);
+    assert!(Pin::new(&mut listener4).poll(&mut cx4).is_pending());
+    assert_eq!(count.get(), 1);
+    drop(listener3);
+    assert!(Pin::new(&mut listener4).poll(&mut cx4).is_pending());
+    assert_eq!(NOTIFY.notify_key(&4), 1);
+    assert!(Pin::new(&mut listener4).poll(&mut cx4).is_ready());
+}
+
+#[test]
+fn test_threads() {
+    const ROUNDS: usize = 10000;
+    static NOTIFY: TaggedNotify<u8, 2> = TaggedNotify::new();
+    let start = Arc::new(Barrier::new(2));
+    let (done, finished) = mpsc::channel();
+
+    let mut listener = NOTIFY.listen(1);
+    // registered before the notices
+    assert!(Pin::new(&mut listener).poll(&mut task::noop_context()).is_pending());
+    let notifier_start = start.clone();
+    thread::spawn(move || {
+        for _ in 0..ROUNDS {
+            notifier_start.wait();
+            NOTIFY.notify_key(&1);
+        }
+    });
+    thread::spawn(move || {
+        for _ in 0..ROUNDS {
+            start.wait();
+            // a notice sent while the waker is set again must not be lost
+            block_on(listener.next());
+        }
+        done.send(()).unwrap();
+    });
+    finished.recv_timeout(Duration::from_secs(10)).unwrap();
+}
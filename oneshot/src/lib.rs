@@ -0,0 +1,231 @@
+#![no_std]
+
+use ach_option::AchOption;
+use async_ach_notify::{Listener, Notify};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use core::task::{Context, Poll};
+
+const TX_TAKEN: usize = 1 << 0;
+const RX_TAKEN: usize = 1 << 1;
+const TX_CLOSED: usize = 1 << 2;
+const RX_CLOSED: usize = 1 << 3;
+const SENT: usize = 1 << 4;
+
+/// The sender has been dropped without sending a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The value has not been sent yet.
+    Empty,
+    /// The sender has been dropped without sending a value.
+    Closed,
+}
+
+/// A slot which transfers a single value.
+///
+/// It can be reused by `reset` after both endpoints are dropped.
+pub struct Oneshot<T> {
+    val: AchOption<T>,
+    state: AtomicUsize,
+    consumer: Notify<1>,
+    producer: Notify<1>,
+}
+impl<T> Default for Oneshot<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> Oneshot<T> {
+    pub const fn new() -> Self {
+        Self {
+            val: AchOption::new(),
+            state: AtomicUsize::new(0),
+            consumer: Notify::new(),
+            producer: Notify::new(),
+        }
+    }
+    pub fn take_sender(&self) -> Option<Sender<'_, T>> {
+        self.take(TX_TAKEN)?;
+        Some(Sender { parent: self })
+    }
+    pub fn take_recver(&self) -> Option<Receiver<'_, T>> {
+        self.take(RX_TAKEN)?;
+        Some(Receiver {
+            parent: self,
+            wait_p: self.producer.listen(),
+        })
+    }
+    /// Takes both endpoints.
+    ///
+    /// Returns None if any endpoint had been taken.
+    pub fn split(&self) -> Option<(Sender<'_, T>, Receiver<'_, T>)> {
+        self.take(TX_TAKEN | RX_TAKEN)?;
+        Some((
+            Sender { parent: self },
+            Receiver {
+                parent: self,
+                wait_p: self.producer.listen(),
+            },
+        ))
+    }
+    /// Drops the unreceived value, and makes the endpoints can be taken again.
+    ///
+    /// Returns false if any endpoint is alive.
+    pub fn reset(&self) -> bool {
+        let alive = |x: usize| {
+            (x & TX_TAKEN != 0 && x & TX_CLOSED == 0) || (x & RX_TAKEN != 0 && x & RX_CLOSED == 0)
+        };
+        // looks like both endpoints are alive while resetting
+        if self
+            .state
+            .fetch_update(SeqCst, SeqCst, |x| {
+                if alive(x) {
+                    None
+                } else {
+                    Some(TX_TAKEN | RX_TAKEN)
+                }
+            })
+            .is_err()
+        {
+            return false;
+        }
+        drop(self.val.take());
+        self.state.store(0, SeqCst);
+        true
+    }
+    fn take(&self, flags: usize) -> Option<()> {
+        self.state
+            .fetch_update(SeqCst, SeqCst, |x| {
+                if x & flags == 0 {
+                    Some(x | flags)
+                } else {
+                    None
+                }
+            })
+            .ok()
+            .map(|_| ())
+    }
+    fn is_closed(&self, flag: usize) -> bool {
+        self.state.load(SeqCst) & flag != 0
+    }
+}
+
+pub struct Sender<'a, T> {
+    parent: &'a Oneshot<T>,
+}
+impl<'a, T> Sender<'a, T> {
+    /// Sends the value to the receiver.
+    ///
+    /// Returns Err if the receiver has been dropped.
+    pub fn send(self, val: T) -> Result<(), T> {
+        if self.is_closed() {
+            return Err(val);
+        }
+        self.parent.val.set(val).map_err(|e| e.input)?;
+        // the receiver may be dropped after the check above, take the value back if so
+        let state = self.parent.state.fetch_or(SENT, SeqCst);
+        if state & RX_CLOSED != 0 {
+            if let Some(val) = self.parent.val.take() {
+                return Err(val);
+            }
+        }
+        Ok(())
+    }
+    /// Returns true if the receiver has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.parent.is_closed(RX_CLOSED)
+    }
+    /// Wait for the receiver is dropped.
+    pub fn closed(&self) -> Closed<'_, T> {
+        Closed {
+            parent: self.parent,
+            wait_c: self.parent.consumer.listen(),
+        }
+    }
+}
+impl<'a, T> Drop for Sender<'a, T> {
+    fn drop(&mut self) {
+        self.parent.state.fetch_or(TX_CLOSED, SeqCst);
+        self.parent.producer.notify_one();
+    }
+}
+
+pub struct Closed<'a, T> {
+    parent: &'a Oneshot<T>,
+    wait_c: Listener<'a, 1>,
+}
+impl<'a, T> Future for Closed<'a, T> {
+    type Output = ();
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            if self.parent.is_closed(RX_CLOSED) {
+                return Poll::Ready(());
+            }
+            if Pin::new(&mut self.wait_c).poll(cx).is_pending() {
+                return if self.parent.is_closed(RX_CLOSED) {
+                    Poll::Ready(())
+                } else {
+                    Poll::Pending
+                };
+            }
+        }
+    }
+}
+
+/// Wait for the value.
+///
+/// Returns Err if the sender has been dropped without sending a value.
+pub struct Receiver<'a, T> {
+    parent: &'a Oneshot<T>,
+    wait_p: Listener<'a, 1>,
+}
+impl<'a, T> Receiver<'a, T> {
+    /// Tries to receive the value.
+    ///
+    /// Returns Err if the value has not been sent or the sender has been dropped.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        if let Some(val) = self.parent.val.take() {
+            return Ok(val);
+        }
+        if self.parent.is_closed(TX_CLOSED) {
+            // the value may be sent before the sender dropped
+            self.parent.val.take().ok_or(TryRecvError::Closed)
+        } else {
+            Err(TryRecvError::Empty)
+        }
+    }
+    /// Closes the receiver, the sender will fail to send.
+    pub fn close(&mut self) {
+        self.parent.state.fetch_or(RX_CLOSED, SeqCst);
+        self.parent.consumer.notify_one();
+    }
+    fn try_poll(&mut self) -> Poll<Result<T, RecvError>> {
+        match self.try_recv() {
+            Ok(val) => Poll::Ready(Ok(val)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError)),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+}
+impl<'a, T> Future for Receiver<'a, T> {
+    type Output = Result<T, RecvError>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            if let Poll::Ready(ret) = self.try_poll() {
+                return Poll::Ready(ret);
+            }
+            if Pin::new(&mut self.wait_p).poll(cx).is_pending() {
+                return self.try_poll();
+            }
+        }
+    }
+}
+impl<'a, T> Drop for Receiver<'a, T> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
@@ -0,0 +1,49 @@
+use async_ach_oneshot::{Oneshot, RecvError, TryRecvError};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static ONESHOT: Oneshot<usize> = Oneshot::new();
+    let mut cx = task::noop_context();
+
+    let (sender, mut recver) = ONESHOT.split().unwrap();
+    assert!(ONESHOT.take_sender().is_none());
+    assert!(!ONESHOT.reset());
+    assert_eq!(recver.try_recv(), Err(TryRecvError::Empty));
+    assert!(Pin::new(&mut recver).poll(&mut cx).is_pending());
+    assert!(sender.send(1).is_ok());
+    assert_eq!(Pin::new(&mut recver).poll(&mut cx), Poll::Ready(Ok(1)));
+    drop(recver);
+
+    assert!(ONESHOT.reset());
+    let (sender, mut recver) = ONESHOT.split().unwrap();
+    assert!(Pin::new(&mut recver).poll(&mut cx).is_pending());
+    drop(sender);
+    assert_eq!(
+        Pin::new(&mut recver).poll(&mut cx),
+        Poll::Ready(Err(RecvError))
+    );
+    drop(recver);
+
+    assert!(ONESHOT.reset());
+    let sender = ONESHOT.take_sender().unwrap();
+    let recver = ONESHOT.take_recver().unwrap();
+    let mut closed = sender.closed();
+    assert!(Pin::new(&mut closed).poll(&mut cx).is_pending());
+    drop(recver);
+    assert!(Pin::new(&mut closed).poll(&mut cx).is_ready());
+    drop(closed);
+    assert_eq!(sender.send(2), Err(2));
+}
+
+#[test]
+fn close() {
+    static ONESHOT: Oneshot<usize> = Oneshot::new();
+    let (sender, mut recver) = ONESHOT.split().unwrap();
+    recver.close();
+    assert_eq!(sender.send(1), Err(1));
+    assert_eq!(recver.try_recv(), Err(TryRecvError::Closed));
+}
@@ -0,0 +1,269 @@
+use crate::atomic::{
+    AtomicU32, AtomicUsize,
+    Ordering::{self, SeqCst},
+};
+use ach_util::{Error, MemoryRing, MemoryState};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+/// The storage of `Ring`.
+pub trait Buffer<T> {
+    fn capacity(&self) -> usize;
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+    /// Appends an element to the back of the buffer.
+    ///
+    /// Returns Err if the buffer is full.
+    fn push(&self, val: T) -> Result<(), Error<T>>;
+    /// Removes the first element and returns it.
+    ///
+    /// Returns Err if the buffer is empty, or with `retry` if the first element is peeked.
+    fn pop(&self) -> Result<T, Error<()>>;
+    /// Calls `f` with the first element in place, pops are rejected until `f` returns.
+    ///
+    /// Returns Err if the buffer is empty, or with `retry` if the first element is peeked.
+    fn peek_with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, Error<()>>;
+}
+
+/// `MemoryRing` on the atomics of this crate, instead of `ach_util::AtomicMemoryRing`,
+/// so it is emulated by `atomic-polyfill` on targets without CAS.
+struct AtomicMemoryRing(AtomicU32);
+impl AtomicMemoryRing {
+    /// `MemoryRing::INIT`
+    const INIT: u32 = 0;
+    fn load(&self, order: Ordering) -> MemoryRing {
+        self.0.load(order).into()
+    }
+    fn store(&self, val: MemoryRing, order: Ordering) {
+        self.0.store(val.into(), order)
+    }
+    fn compare_exchange(
+        &self,
+        current: MemoryRing,
+        new: MemoryRing,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<MemoryRing, MemoryRing> {
+        self.0
+            .compare_exchange(current.into(), new.into(), success, failure)
+            .map(Into::into)
+            .map_err(Into::into)
+    }
+}
+
+/// An element of `SlotBuffer` and its state.
+pub struct Slot<T> {
+    val: UnsafeCell<MaybeUninit<T>>,
+    op: AtomicMemoryRing,
+}
+unsafe impl<T: Send> Send for Slot<T> {}
+unsafe impl<T: Send> Sync for Slot<T> {}
+impl<T> Slot<T> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub(crate) const INIT: Self = Self {
+        val: UnsafeCell::new(MaybeUninit::uninit()),
+        op: AtomicMemoryRing(AtomicU32::new(AtomicMemoryRing::INIT)),
+    };
+}
+impl<T> Drop for Slot<T> {
+    fn drop(&mut self) {
+        if MemoryRing::from(*self.op.0.get_mut())
+            .state()
+            .is_initialized()
+        {
+            unsafe { self.val.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// The lock free buffer of `ach_ring::Ring`, on an array or a boxed slice of slots.
+///
+/// A pop claims the slot of the first element before moving the start,
+/// so a peek can hold the slot in place and reject the pops until it returns.
+///
+/// Like `ach_ring::Ring`, pushes and pops run with interrupts disabled on bare metal targets,
+/// peeks do not, as `f` may run for long.
+pub struct SlotBuffer<S> {
+    slots: S,
+    /// always points to the first element
+    start: AtomicUsize,
+    end: AtomicUsize,
+    wrap_max: usize,
+}
+/// The default buffer of `Ring`.
+pub type ArrayBuffer<T, const N: usize> = SlotBuffer<[Slot<T>; N]>;
+
+impl<T, const N: usize> Default for ArrayBuffer<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize> ArrayBuffer<T, N> {
+    pub const fn new() -> Self {
+        Self::from_slots([Slot::INIT; N], N)
+    }
+}
+impl<S> SlotBuffer<S> {
+    pub(crate) const fn from_slots(slots: S, capacity: usize) -> Self {
+        Self {
+            slots,
+            start: AtomicUsize::new(0),
+            end: AtomicUsize::new(0),
+            wrap_max: MemoryRing::max_idx(capacity),
+        }
+    }
+    fn wrap_len(&self, start: usize, end: usize) -> usize {
+        if end >= start {
+            end - start
+        } else {
+            self.wrap_max - start + end
+        }
+    }
+    #[inline]
+    fn next_idx(&self, old: usize) -> usize {
+        if old == self.wrap_max - 1 {
+            0
+        } else {
+            old + 1
+        }
+    }
+    #[inline]
+    fn slot<T>(&self, idx: usize) -> &Slot<T>
+    where
+        S: AsRef<[Slot<T>]>,
+    {
+        let slots = self.slots.as_ref();
+        &slots[idx % slots.len()]
+    }
+    /// Returns the index and the cycle of the first element.
+    fn first<T>(&self) -> Result<(usize, usize), Error<()>>
+    where
+        S: AsRef<[Slot<T>]>,
+    {
+        let mut start = self.start.load(SeqCst);
+        loop {
+            let slot: &Slot<T> = self.slot(start);
+            let cycle = MemoryRing::cycle_of_idx(start, self.slots.as_ref().len());
+            let initialized = MemoryRing::new(cycle, MemoryState::Initialized);
+            let op = slot.op.load(Ordering::Acquire);
+            if op == initialized {
+                return Ok((start, cycle));
+            } else if op == MemoryRing::new(cycle, MemoryState::Erasing) {
+                // claimed by a pop which has not moved the start yet, move it for the pop
+                let _ = self
+                    .start
+                    .compare_exchange(start, self.next_idx(start), SeqCst, SeqCst);
+            } else if op == MemoryRing::new(cycle, MemoryState::Regaining) {
+                return Err(crate::busy(()));
+            } else if op < initialized {
+                return Err(Error {
+                    state: op.state(),
+                    input: (),
+                    retry: false,
+                });
+            }
+            start = self.start.load(SeqCst);
+        }
+    }
+}
+impl<T, S: AsRef<[Slot<T>]>> Buffer<T> for SlotBuffer<S> {
+    fn capacity(&self) -> usize {
+        self.slots.as_ref().len()
+    }
+    fn len(&self) -> usize {
+        let start = self.start.load(SeqCst);
+        let end = self.end.load(SeqCst);
+        self.wrap_len(start, end)
+    }
+    fn push(&self, val: T) -> Result<(), Error<T>> {
+        #[cfg(target_os = "none")]
+        let _cs = interrupt::CriticalSection::new();
+        let mut end = self.end.load(SeqCst);
+        loop {
+            let cycle = MemoryRing::cycle_of_idx(end, self.capacity());
+            let slot = self.slot(end);
+            let expect = MemoryRing::new(cycle, MemoryState::Uninitialized);
+            let op = slot.op.load(Ordering::Acquire);
+            if op < expect {
+                return Err(Error {
+                    state: op.state(),
+                    input: val,
+                    retry: false,
+                });
+            }
+            if let Err(i) = self
+                .end
+                .compare_exchange(end, self.next_idx(end), SeqCst, SeqCst)
+            {
+                end = i;
+                continue;
+            }
+            unsafe { ptr::write(slot.val.get(), MaybeUninit::new(val)) };
+            let op = MemoryRing::new(cycle, MemoryState::Initialized);
+            slot.op.store(op, Ordering::Release);
+            return Ok(());
+        }
+    }
+    fn pop(&self) -> Result<T, Error<()>> {
+        #[cfg(target_os = "none")]
+        let _cs = interrupt::CriticalSection::new();
+        loop {
+            let (start, cycle) = self.first::<T>()?;
+            let slot = self.slot(start);
+            let initialized = MemoryRing::new(cycle, MemoryState::Initialized);
+            let claimed = MemoryRing::new(cycle, MemoryState::Erasing);
+            if slot
+                .op
+                .compare_exchange(initialized, claimed, SeqCst, SeqCst)
+                .is_err()
+            {
+                continue;
+            }
+            // `first` moves it if this pop is preempted here
+            let _ = self
+                .start
+                .compare_exchange(start, self.next_idx(start), SeqCst, SeqCst);
+            let ret = unsafe { ptr::read(slot.val.get()).assume_init() };
+            let op = MemoryRing::new(cycle + 1, MemoryState::Uninitialized);
+            slot.op.store(op, Ordering::Release);
+            return Ok(ret);
+        }
+    }
+    fn peek_with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, Error<()>> {
+        loop {
+            let (start, cycle) = self.first::<T>()?;
+            let slot = self.slot(start);
+            let initialized = MemoryRing::new(cycle, MemoryState::Initialized);
+            let peeked = MemoryRing::new(cycle, MemoryState::Regaining);
+            if slot
+                .op
+                .compare_exchange(initialized, peeked, SeqCst, SeqCst)
+                .is_err()
+            {
+                continue;
+            }
+            let _guard = Peeked {
+                op: &slot.op,
+                initialized,
+            };
+            return Ok(f(unsafe { (*slot.val.get()).assume_init_ref() }));
+        }
+    }
+}
+
+/// Releases the peeked slot, even if the peek panics.
+struct Peeked<'a> {
+    op: &'a AtomicMemoryRing,
+    initialized: MemoryRing,
+}
+impl<'a> Drop for Peeked<'a> {
+    fn drop(&mut self) {
+        self.op.store(self.initialized, Ordering::Release);
+    }
+}
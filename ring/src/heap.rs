@@ -0,0 +1,34 @@
+use crate::buffer::{Buffer, Slot, SlotBuffer};
+use alloc::boxed::Box;
+use async_ach_notify::{Notifier, Notify};
+
+/// A Ring with the capacity chosen at runtime.
+///
+/// `C` and `P` are the notify backends of consumers and producers, see `crate::Ring`.
+pub type Ring<T, const MP: usize, const MC: usize, C = Notify<MP>, P = Notify<MC>> =
+    crate::Ring<T, 0, MP, MC, C, P, HeapBuffer<T>>;
+
+impl<T, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Ring<T, MP, MC, C, P> {
+    /// Panics if `capacity` is 0.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_buffer(HeapBuffer::new(capacity), false)
+    }
+    /// Creates a Ring in which `try_push` and `push` evict the oldest element if full, see `crate::Ring::new_overwrite`.
+    pub fn with_capacity_overwrite(capacity: usize) -> Self {
+        Self::with_buffer(HeapBuffer::new(capacity), true)
+    }
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+}
+
+/// The buffer of `Ring`, allocated on the heap.
+pub type HeapBuffer<T> = SlotBuffer<Box<[Slot<T>]>>;
+
+impl<T> HeapBuffer<T> {
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity != 0, "capacity must not be 0");
+        Self::from_slots((0..capacity).map(|_| Slot::INIT).collect(), capacity)
+    }
+}
@@ -1,62 +1,304 @@
 #![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
-use ach_ring as ach;
-use ach_util::Error;
-use async_ach_notify::Notify;
-use futures_util::StreamExt;
+mod buffer;
+#[cfg(feature = "alloc")]
+pub mod heap;
 
-pub struct Ring<T, const N: usize, const MP: usize, const MC: usize> {
-    buf: ach::Ring<T, N>,
-    consumer: Notify<MP>,
-    producer: Notify<MC>,
+pub use buffer::{ArrayBuffer, Buffer, Slot, SlotBuffer};
+
+use ach_util::{Error, MemoryState};
+use async_ach_notify::{Notifier, Notify};
+use atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst};
+use core::marker::PhantomData;
+use futures_util::{pin_mut, StreamExt};
+
+#[cfg(loom)]
+use async_ach_notify::loom_atomic as atomic;
+#[cfg(all(not(loom), feature = "critical-section"))]
+use atomic_polyfill as atomic;
+#[cfg(all(not(loom), not(feature = "critical-section")))]
+use core::sync::atomic;
+
+fn busy<I>(input: I) -> Error<I> {
+    Error {
+        state: MemoryState::Regaining,
+        input,
+        retry: true,
+    }
+}
+
+/// `C` and `P` are the notify backends of consumers and producers.
+///
+/// Use `IntrusiveNotify` if the number of waiters is unknown, then `MP` and `MC` are ignored.
+///
+/// `B` is the buffer, `N` is ignored if it is not the default one, see `heap::Ring`.
+pub struct Ring<
+    T,
+    const N: usize,
+    const MP: usize,
+    const MC: usize,
+    C = Notify<MP>,
+    P = Notify<MC>,
+    B = ArrayBuffer<T, N>,
+> {
+    buf: B,
+    /// the elements are owned by `buf`
+    _val: PhantomData<fn() -> T>,
+    consumer: C,
+    producer: P,
+    /// a pop was rejected by a peek
+    contended: AtomicBool,
+    /// push evicts the oldest element if full
+    overwrite: bool,
+    /// number of evicted elements
+    overflowed: AtomicUsize,
+}
+impl<T, const N: usize, const MP: usize, const MC: usize, C: Notifier, P: Notifier> Default
+    for Ring<T, N, MP, MC, C, P>
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
-impl<T, const N: usize, const MP: usize, const MC: usize> Ring<T, N, MP, MC> {
+impl<T, const N: usize, const MP: usize, const MC: usize, C: Notifier, P: Notifier>
+    Ring<T, N, MP, MC, C, P>
+{
     pub const fn new() -> Self {
+        Self::with_buffer(ArrayBuffer::new(), false)
+    }
+    /// Creates a Ring in which pushes evict the oldest element if full, instead of failing or waiting.
+    ///
+    /// `force_push` returns the evicted element, `try_push` and `push` drop it.
+    pub const fn new_overwrite() -> Self {
+        Self::with_buffer(ArrayBuffer::new(), true)
+    }
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+impl<
+        T,
+        const N: usize,
+        const MP: usize,
+        const MC: usize,
+        C: Notifier,
+        P: Notifier,
+        B: Buffer<T>,
+    > Ring<T, N, MP, MC, C, P, B>
+{
+    pub(crate) const fn with_buffer(buf: B, overwrite: bool) -> Self {
         Self {
-            buf: ach::Ring::new(),
-            consumer: Notify::new(),
-            producer: Notify::new(),
+            buf,
+            _val: PhantomData,
+            consumer: C::NEW,
+            producer: P::NEW,
+            contended: AtomicBool::new(false),
+            overwrite,
+            overflowed: AtomicUsize::new(0),
         }
     }
+    pub const fn is_overwrite(&self) -> bool {
+        self.overwrite
+    }
+    /// Returns the number of elements evicted by `force_push` or in overwrite mode.
+    pub fn overflowed(&self) -> usize {
+        self.overflowed.load(SeqCst)
+    }
+    /// Returns the number of evicted elements, and resets it.
+    pub fn take_overflowed(&self) -> usize {
+        self.overflowed.swap(0, SeqCst)
+    }
     pub fn len(&self) -> usize {
         self.buf.len()
     }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns true if `try_push` would fail, or evict the oldest element in overwrite mode.
+    pub fn is_full(&self) -> bool {
+        self.buf.is_full()
+    }
+    /// Waits until the Ring is not empty, without removing any element.
+    pub async fn wait_not_empty(&self) {
+        let parked = {
+            let wait_p = self.producer.listen();
+            pin_mut!(wait_p);
+            let mut parked = false;
+            while self.is_empty() {
+                wait_p.next().await;
+                parked = true;
+            }
+            parked
+        };
+        if parked {
+            // the notice may be sent for a pop, pass it on
+            self.producer.notify_one();
+        }
+    }
+    /// Waits until the Ring is not full, without adding any element.
+    pub async fn wait_not_full(&self) {
+        let parked = {
+            let wait_c = self.consumer.listen();
+            pin_mut!(wait_c);
+            let mut parked = false;
+            while self.is_full() {
+                wait_c.next().await;
+                parked = true;
+            }
+            parked
+        };
+        if parked {
+            // the notice may be sent for a push, pass it on
+            self.consumer.notify_one();
+        }
+    }
 }
-impl<T: Unpin, const N: usize, const MP: usize, const MC: usize> Ring<T, N, MP, MC> {
+/// Notify both sides if any pop was rejected by the peek, even if the peek panics.
+struct Unpeek<'a, T, const N: usize, const MP: usize, const MC: usize, C, P, B>
+where
+    C: Notifier,
+    P: Notifier,
+    B: Buffer<T>,
+{
+    parent: &'a Ring<T, N, MP, MC, C, P, B>,
+}
+impl<'a, T, const N: usize, const MP: usize, const MC: usize, C, P, B> Drop
+    for Unpeek<'a, T, N, MP, MC, C, P, B>
+where
+    C: Notifier,
+    P: Notifier,
+    B: Buffer<T>,
+{
+    fn drop(&mut self) {
+        if self.parent.contended.swap(false, SeqCst) {
+            self.parent.producer.notify_one();
+            self.parent.consumer.notify_one();
+        }
+    }
+}
+
+impl<
+        T: Unpin,
+        const N: usize,
+        const MP: usize,
+        const MC: usize,
+        C: Notifier,
+        P: Notifier,
+        B: Buffer<T>,
+    > Ring<T, N, MP, MC, C, P, B>
+{
     /// Appends an element to the back of the Ring.
     ///
-    /// Returns Err if the Ring is full or in critical section.
+    /// Returns Err if the Ring is full.
+    /// In overwrite mode, the oldest element is dropped if full, see `force_push`.
     pub fn try_push(&self, val: T) -> Result<(), Error<T>> {
-        self.buf.push(val).map(|x| {
+        if self.overwrite {
+            self.force_push(val).map(drop)
+        } else {
+            self.push_back(val)
+        }
+    }
+    /// Appends an element to the back of the Ring, evicts the oldest element and returns it if full.
+    ///
+    /// Returns Err with `retry` if the first element is peeked, or is being written by another producer.
+    ///
+    /// Notice: if other producers fill the Ring at the same time, more elements may be evicted,
+    /// the first evicted one is returned and the others are dropped.
+    pub fn force_push(&self, mut val: T) -> Result<Option<T>, Error<T>> {
+        let mut evicted = None;
+        let mut stalled = false;
+        loop {
+            match self.push_back(val) {
+                Ok(()) => return Ok(evicted),
+                Err(err) => val = err.input,
+            }
+            match self.try_pop() {
+                Ok(old) => {
+                    stalled = false;
+                    self.overflowed.fetch_add(1, SeqCst);
+                    if evicted.is_none() {
+                        evicted = Some(old);
+                    }
+                }
+                Err(err) if err.retry || stalled => {
+                    return Err(Error {
+                        state: err.state,
+                        input: val,
+                        retry: true,
+                    })
+                }
+                // emptied by the consumers, or being written by a producer
+                Err(_) => stalled = true,
+            }
+        }
+    }
+    fn push_back(&self, val: T) -> Result<(), Error<T>> {
+        self.buf.push(val).inspect(|_| {
             self.producer.notify_one();
-            x
+            if self.overwrite {
+                // a push stalled on this element waits for the consumer notify, see `push`
+                self.consumer.notify_one();
+            }
         })
     }
     /// Appends an element to the back of the Ring.
+    ///
+    /// In overwrite mode, the oldest element is dropped if full, see `force_push`.
+    /// If it can not be evicted, the push waits until it is popped, peeked or written.
     pub async fn push(&self, mut val: T) {
-        let mut wait_c = self.consumer.listen();
-        loop {
-            if let Err(err) = self.try_push(val) {
-                val = err.input;
-                wait_c.next().await;
-            } else {
-                break;
-            }
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_c);
+        while let Err(err) = self.try_push(val) {
+            val = err.input;
+            wait_c.next().await;
         }
     }
 
     /// Removes the first element and returns it.
     ///
-    /// Returns Err if the Ring is empty or in critical section.
+    /// Returns Err if the Ring is empty, or the first element is peeked.
     pub fn try_pop(&self) -> Result<T, Error<()>> {
-        self.buf.pop().map(|x| {
-            self.consumer.notify_one();
-            x
-        })
+        let ret = match self.buf.pop() {
+            Err(err) if err.retry => {
+                // the peek notifies on returning, check again to not miss the notice.
+                self.contended.store(true, SeqCst);
+                self.buf.pop()
+            }
+            ret => ret,
+        };
+        ret.inspect(|_| self.consumer.notify_one())
+    }
+    /// Calls `f` with the first element in place, without removing it.
+    ///
+    /// Returns Err if the Ring is empty, or the first element is peeked by another caller.
+    ///
+    /// Notice: pops are rejected until `f` returns, and notified then. Pushes go on.
+    pub fn peek_with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, Error<()>> {
+        let _guard = Unpeek { parent: self };
+        self.buf.peek_with(f)
+    }
+    /// Returns a copy of the first element, without removing it.
+    ///
+    /// Returns Err if the Ring is empty or in critical section.
+    pub fn peek(&self) -> Result<T, Error<()>>
+    where
+        T: Clone,
+    {
+        self.peek_with(T::clone)
     }
     /// Removes the first element and returns it.
     pub async fn pop(&self) -> T {
-        let mut wait_p = self.producer.listen();
+        self.pop_with_priority(0).await
+    }
+    /// Removes the first element and returns it.
+    ///
+    /// If several consumers are parked, the one with the highest `priority` is woken first,
+    /// when the producer notify `P` is a `PriorityNotify`. Otherwise the priority is ignored.
+    pub async fn pop_with_priority(&self, priority: usize) -> T {
+        let wait_p = self.producer.listen_with_priority(priority);
+        pin_mut!(wait_p);
         loop {
             if let Ok(v) = self.try_pop() {
                 break v;
@@ -0,0 +1,29 @@
+#![cfg(feature = "critical-section")]
+
+use async_ach_ring::Ring;
+use core::future::Future;
+use core::task::Poll;
+use futures_test::task;
+use std::thread;
+
+#[test]
+fn test() {
+    static RING: Ring<usize, 2, 1, 1> = Ring::new();
+    let mut cx = task::noop_context();
+
+    let mut pop = Box::pin(RING.pop());
+    assert!(pop.as_mut().poll(&mut cx).is_pending());
+    // push from an "interrupt"
+    thread::spawn(|| {
+        critical_section::with(|_| {
+            RING.try_push(1).unwrap();
+            RING.try_push(2).unwrap();
+            assert!(RING.try_push(3).is_err());
+        })
+    })
+    .join()
+    .unwrap();
+    assert_eq!(RING.len(), 2);
+    assert_eq!(pop.as_mut().poll(&mut cx), Poll::Ready(1));
+    critical_section::with(|_| assert_eq!(RING.try_pop().unwrap(), 2));
+}
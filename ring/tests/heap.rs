@@ -0,0 +1,110 @@
+#![cfg(feature = "alloc")]
+
+use async_ach_ring::heap::Ring;
+use futures_executor::ThreadPool;
+use std::sync::Arc;
+
+#[test]
+fn test() {
+    let ring: Ring<usize, 1, 1> = Ring::with_capacity(3);
+    assert_eq!(ring.capacity(), 3);
+    assert!(ring.is_empty());
+    for i in 0..3 {
+        ring.try_push(i).unwrap();
+    }
+    assert!(ring.is_full());
+    assert_eq!(ring.try_push(3).unwrap_err().input, 3);
+    assert_eq!(ring.peek().unwrap(), 0);
+    assert_eq!(ring.len(), 3);
+    for i in 0..3 {
+        assert_eq!(ring.try_pop().unwrap(), i);
+    }
+    assert!(ring.try_pop().is_err());
+}
+
+#[test]
+fn overwrite() {
+    let ring: Ring<usize, 1, 1> = Ring::with_capacity_overwrite(2);
+    for i in 0..5 {
+        ring.try_push(i).unwrap();
+    }
+    assert_eq!(ring.take_overflowed(), 3);
+    assert_eq!(ring.try_pop().unwrap(), 3);
+    assert_eq!(ring.try_pop().unwrap(), 4);
+}
+
+#[test]
+fn drop_elements() {
+    let val = Arc::new(());
+    let ring: Ring<Arc<()>, 1, 1> = Ring::with_capacity(2);
+    ring.try_push(val.clone()).unwrap();
+    ring.try_push(val.clone()).unwrap();
+    drop(ring);
+    assert_eq!(Arc::strong_count(&val), 1);
+}
+
+#[test]
+fn mpmc() {
+    let ring: Arc<Ring<usize, 4, 4>> = Arc::new(Ring::with_capacity(5));
+    let executor = ThreadPool::new().unwrap();
+    for _ in 0..4 {
+        let ring = ring.clone();
+        executor.spawn_ok(async move {
+            for i in 0..1000 {
+                ring.push(i).await;
+            }
+        });
+    }
+    let sum = futures_executor::block_on(async {
+        let mut sum = 0;
+        for _ in 0..4000 {
+            sum += ring.pop().await;
+        }
+        sum
+    });
+    assert_eq!(sum, 4 * 999 * 1000 / 2);
+}
+
+#[test]
+fn stress() {
+    const COUNT: usize = 10000;
+    let ring: Arc<Ring<usize, 2, 2>> = Arc::new(Ring::with_capacity(3));
+    let producers: Vec<_> = (0..2)
+        .map(|_| {
+            let ring = ring.clone();
+            std::thread::spawn(move || {
+                for i in 0..COUNT {
+                    while ring.try_push(i).is_err() {
+                        std::thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+    let consumers: Vec<_> = (0..2)
+        .map(|_| {
+            let ring = ring.clone();
+            std::thread::spawn(move || {
+                let mut sum = 0;
+                for _ in 0..COUNT {
+                    loop {
+                        // peeks reject the pops of the other consumer for a while
+                        let _ = ring.peek();
+                        if let Ok(v) = ring.try_pop() {
+                            sum += v;
+                            break;
+                        }
+                        std::thread::yield_now();
+                    }
+                }
+                sum
+            })
+        })
+        .collect();
+    for th in producers {
+        th.join().unwrap();
+    }
+    let sum: usize = consumers.into_iter().map(|th| th.join().unwrap()).sum();
+    assert_eq!(sum, 2 * COUNT * (COUNT - 1) / 2);
+    assert!(ring.is_empty());
+}
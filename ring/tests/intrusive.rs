@@ -0,0 +1,78 @@
+use async_ach_notify::IntrusiveNotify;
+use async_ach_ring::Ring;
+use core::future::Future;
+use core::task::Poll;
+use core::time::Duration;
+use futures_executor::{block_on, ThreadPool};
+use futures_test::task;
+use std::collections::BTreeSet;
+use std::sync::mpsc;
+use std::thread;
+
+#[test]
+fn test() {
+    static RING: Ring<usize, 1, 0, 0, IntrusiveNotify, IntrusiveNotify> = Ring::new();
+    let mut cx = task::noop_context();
+
+    let mut pop1 = Box::pin(RING.pop());
+    let mut pop2 = Box::pin(RING.pop());
+    let mut pop3 = Box::pin(RING.pop());
+    assert!(pop1.as_mut().poll(&mut cx).is_pending());
+    assert!(pop2.as_mut().poll(&mut cx).is_pending());
+    assert!(pop3.as_mut().poll(&mut cx).is_pending());
+    drop(pop2);
+
+    let mut push1 = Box::pin(RING.push(1));
+    assert!(push1.as_mut().poll(&mut cx).is_ready());
+    let mut push2 = Box::pin(RING.push(2));
+    assert!(push2.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(pop1.as_mut().poll(&mut cx), Poll::Ready(1));
+    assert!(push2.as_mut().poll(&mut cx).is_ready());
+    assert_eq!(pop3.as_mut().poll(&mut cx), Poll::Ready(2));
+}
+
+#[test]
+fn test_threads() {
+    const TEST_TIMES: usize = 10000;
+    static RING: Ring<usize, 2, 0, 0, IntrusiveNotify, IntrusiveNotify> = Ring::new();
+    let (done, finished) = mpsc::channel();
+
+    for producer in 0..2 {
+        thread::spawn(move || {
+            for i in producer * TEST_TIMES..(producer + 1) * TEST_TIMES {
+                block_on(RING.push(i));
+            }
+        });
+    }
+    thread::spawn(move || {
+        let mut data_set: BTreeSet<usize> = (0..2 * TEST_TIMES).collect();
+        for _ in 0..2 * TEST_TIMES {
+            let data = block_on(RING.pop());
+            assert!(data_set.remove(&data));
+        }
+        done.send(data_set.is_empty()).unwrap();
+    });
+    // a lost wakeup leaves the consumer or a producer parked
+    assert!(finished.recv_timeout(Duration::from_secs(10)).unwrap());
+}
+
+#[test]
+fn test_thread_pool() {
+    const TEST_TIMES: usize = 1000;
+    static RING: Ring<usize, 2, 0, 0, IntrusiveNotify, IntrusiveNotify> = Ring::new();
+    let executor = ThreadPool::new().unwrap();
+    let (done, finished) = mpsc::channel();
+
+    executor.spawn_ok(async {
+        for i in 0..TEST_TIMES {
+            RING.push(i).await;
+        }
+    });
+    executor.spawn_ok(async move {
+        for i in 0..TEST_TIMES {
+            assert_eq!(RING.pop().await, i);
+        }
+        done.send(()).unwrap();
+    });
+    finished.recv_timeout(Duration::from_secs(10)).unwrap();
+}
@@ -0,0 +1,64 @@
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=3 cargo test --test loom --release`.
+#![cfg(loom)]
+
+use async_ach_ring::Ring;
+use loom::future::block_on;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn push_pop() {
+    loom::model(|| {
+        let ring: Arc<Ring<usize, 1, 1, 1>> = Arc::new(Ring::new());
+
+        let producer = ring.clone();
+        let th = thread::spawn(move || {
+            block_on(producer.push(1));
+            block_on(producer.push(2));
+        });
+        assert_eq!(block_on(ring.pop()), 1);
+        assert_eq!(block_on(ring.pop()), 2);
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn peek_pop() {
+    loom::model(|| {
+        let ring: Arc<Ring<usize, 1, 1, 1>> = Arc::new(Ring::new());
+        ring.try_push(1).unwrap();
+
+        let peeker = ring.clone();
+        let th = thread::spawn(move || {
+            // fails if the element is popped, or is being popped
+            if let Ok(v) = peeker.peek() {
+                assert_eq!(v, 1);
+            }
+        });
+        // a pop rejected by the peek waits for its notice
+        assert_eq!(block_on(ring.pop()), 1);
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn force_push_pop() {
+    loom::model(|| {
+        let ring: Arc<Ring<usize, 1, 1, 1>> = Arc::new(Ring::new_overwrite());
+        ring.try_push(1).unwrap();
+
+        let producer = ring.clone();
+        let th = thread::spawn(move || {
+            // parks while the first element can not be evicted
+            block_on(producer.push(2));
+        });
+        let popped = ring.try_pop().ok();
+        th.join().unwrap();
+        let remained = ring.try_pop().ok();
+
+        // the newest element is never evicted, each one is received or evicted once
+        assert!(popped == Some(2) || remained == Some(2));
+        let received = popped.is_some() as usize + remained.is_some() as usize;
+        assert_eq!(received + ring.take_overflowed(), 2);
+    });
+}
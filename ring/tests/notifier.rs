@@ -0,0 +1,84 @@
+use async_ach_notify::Notifier;
+use async_ach_ring::Ring;
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
+use core::task::{Context, Poll, Waker};
+use futures_test::task;
+use futures_util::Stream;
+use std::sync::Mutex;
+
+/// A notifier backed by a std `Mutex`, as a downstream crate would write.
+struct MutexNotify {
+    permit: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+}
+impl Notifier for MutexNotify {
+    const NEW: Self = MutexNotify {
+        permit: AtomicUsize::new(0),
+        wakers: Mutex::new(Vec::new()),
+    };
+    type Listener<'a> = MutexListener<'a>;
+    fn listen(&self) -> Self::Listener<'_> {
+        MutexListener { parent: self }
+    }
+    fn notify_one(&self) {
+        self.permit.fetch_add(1, SeqCst);
+        if let Some(waker) = self.wakers.lock().unwrap().pop() {
+            waker.wake();
+        }
+    }
+    fn notify_all(&self) -> usize {
+        let wakers: Vec<Waker> = self.wakers.lock().unwrap().drain(..).collect();
+        self.permit.fetch_add(wakers.len(), SeqCst);
+        let num = wakers.len();
+        wakers.into_iter().for_each(Waker::wake);
+        num
+    }
+}
+
+struct MutexListener<'a> {
+    parent: &'a MutexNotify,
+}
+impl<'a> Stream for MutexListener<'a> {
+    type Item = ();
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        self.parent.wakers.lock().unwrap().push(cx.waker().clone());
+        let permit = &self.parent.permit;
+        if permit
+            .fetch_update(SeqCst, SeqCst, |x| x.checked_sub(1))
+            .is_ok()
+        {
+            Poll::Ready(Some(()))
+        } else {
+            Poll::Pending
+        }
+    }
+}
+impl<'a> Future for MutexListener<'a> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.poll_next(cx).map(|_| ())
+    }
+}
+
+#[test]
+fn test() {
+    static RING: Ring<usize, 1, 0, 0, MutexNotify, MutexNotify> = Ring::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = Context::from_waker(&waker);
+
+    let mut pop1 = Box::pin(RING.pop());
+    assert!(pop1.as_mut().poll(&mut cx).is_pending());
+    let mut push1 = Box::pin(RING.push(1));
+    assert!(push1.as_mut().poll(&mut cx).is_ready());
+    assert_eq!(count.get(), 1);
+    assert_eq!(pop1.as_mut().poll(&mut cx), Poll::Ready(1));
+
+    let mut push2 = Box::pin(RING.push(2));
+    assert!(push2.as_mut().poll(&mut cx).is_ready());
+    let mut push3 = Box::pin(RING.push(3));
+    assert!(push3.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(RING.try_pop().unwrap(), 2);
+    assert!(push3.as_mut().poll(&mut cx).is_ready());
+}
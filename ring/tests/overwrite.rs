@@ -0,0 +1,70 @@
+use async_ach_ring::Ring;
+
+#[test]
+fn test() {
+    static RING: Ring<usize, 2, 1, 1> = Ring::new_overwrite();
+    assert!(RING.is_overwrite());
+
+    RING.try_push(1).unwrap();
+    RING.try_push(2).unwrap();
+    RING.try_push(3).unwrap();
+    assert_eq!(RING.len(), 2);
+    assert_eq!(RING.force_push(4).unwrap(), Some(2));
+    assert_eq!(RING.overflowed(), 2);
+    assert_eq!(RING.take_overflowed(), 2);
+    assert_eq!(RING.overflowed(), 0);
+
+    assert_eq!(RING.try_pop().unwrap(), 3);
+    assert_eq!(RING.force_push(5).unwrap(), None);
+    assert_eq!(RING.try_pop().unwrap(), 4);
+    assert_eq!(RING.try_pop().unwrap(), 5);
+}
+
+#[test]
+fn force_push() {
+    static RING: Ring<usize, 1, 1, 1> = Ring::new();
+    RING.try_push(1).unwrap();
+    assert!(RING.try_push(2).is_err());
+    assert_eq!(RING.force_push(2).unwrap(), Some(1));
+    assert_eq!(RING.overflowed(), 1);
+
+    assert_eq!(RING.peek().unwrap(), 2);
+    assert_eq!(RING.force_push(3).unwrap(), Some(2));
+    // the peeked element is not evicted
+    RING.peek_with(|x| {
+        assert_eq!(*x, 3);
+        assert!(RING.force_push(4).unwrap_err().retry);
+    })
+    .unwrap();
+    assert_eq!(RING.try_pop().unwrap(), 3);
+}
+
+#[test]
+fn threads() {
+    const COUNT: usize = 10000;
+    static RING: Ring<usize, 2, 2, 1> = Ring::new_overwrite();
+
+    let producers: Vec<_> = (0..2)
+        .map(|_| {
+            std::thread::spawn(|| {
+                for i in 0..COUNT {
+                    futures_executor::block_on(RING.push(i));
+                }
+            })
+        })
+        .collect();
+    let mut received = 0;
+    loop {
+        let done = producers.iter().all(|th| th.is_finished());
+        while RING.try_pop().is_ok() {
+            received += 1;
+        }
+        if done {
+            break;
+        }
+    }
+    for th in producers {
+        th.join().unwrap();
+    }
+    assert_eq!(received + RING.take_overflowed(), 2 * COUNT);
+}
@@ -0,0 +1,135 @@
+use async_ach_ring::Ring;
+use core::future::Future;
+use core::sync::atomic::{AtomicBool, Ordering::SeqCst};
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn peek() {
+    static RING: Ring<usize, 2, 1, 1> = Ring::new();
+    assert_eq!(RING.capacity(), 2);
+    assert!(RING.is_empty());
+    assert!(RING.peek().is_err());
+
+    RING.try_push(1).unwrap();
+    RING.try_push(2).unwrap();
+    assert!(RING.is_full());
+    assert_eq!(RING.peek().unwrap(), 1);
+    assert_eq!(RING.peek_with(|x| x + 10).unwrap(), 11);
+    // the peeked element stays in place
+    assert_eq!(RING.len(), 2);
+    assert!(RING.is_full());
+    assert!(RING.try_push(3).is_err());
+
+    assert_eq!(RING.try_pop().unwrap(), 1);
+    assert_eq!(RING.try_pop().unwrap(), 2);
+    assert!(RING.is_empty());
+}
+
+#[test]
+fn peek_reject_pop() {
+    static RING: Ring<usize, 2, 1, 1> = Ring::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    RING.try_push(1).unwrap();
+    let mut pop = Box::pin(RING.pop());
+    RING.peek_with(|x| {
+        assert_eq!(*x, 1);
+        assert!(RING.try_pop().unwrap_err().retry);
+        assert!(RING.peek().unwrap_err().retry);
+        assert!(pop.as_mut().poll(&mut cx).is_pending());
+        // pushes go on
+        RING.try_push(2).unwrap();
+    })
+    .unwrap();
+    assert!(count.get() > 0);
+    assert_eq!(pop.as_mut().poll(&mut cx), Poll::Ready(1));
+    assert_eq!(RING.try_pop().unwrap(), 2);
+}
+
+#[test]
+fn peek_panic() {
+    static RING: Ring<usize, 2, 1, 1> = Ring::new();
+    RING.try_push(1).unwrap();
+    let ret = std::panic::catch_unwind(|| RING.peek_with(|_| panic!("peek")));
+    assert!(ret.is_err());
+    // the element is released
+    assert_eq!(RING.peek().unwrap(), 1);
+    assert_eq!(RING.try_pop().unwrap(), 1);
+}
+
+#[test]
+fn wait() {
+    static RING: Ring<usize, 1, 1, 1> = Ring::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    let mut not_empty = Box::pin(RING.wait_not_empty());
+    assert!(not_empty.as_mut().poll(&mut cx).is_pending());
+    RING.try_push(1).unwrap();
+    assert_eq!(count.get(), 1);
+    assert_eq!(not_empty.as_mut().poll(&mut cx), Poll::Ready(()));
+    drop(not_empty);
+    assert_eq!(RING.len(), 1);
+
+    let mut not_full = Box::pin(RING.wait_not_full());
+    assert!(not_full.as_mut().poll(&mut cx).is_pending());
+    assert_eq!(RING.try_pop().unwrap(), 1);
+    assert_eq!(count.get(), 2);
+    assert_eq!(not_full.as_mut().poll(&mut cx), Poll::Ready(()));
+    drop(not_full);
+    assert!(RING.is_empty());
+
+    // the notice is passed on to the pop
+    let (pop_waker, pop_count) = task::new_count_waker();
+    let mut not_empty = Box::pin(RING.wait_not_empty());
+    let mut pop = Box::pin(RING.pop());
+    assert!(not_empty.as_mut().poll(&mut cx).is_pending());
+    assert!(pop
+        .as_mut()
+        .poll(&mut core::task::Context::from_waker(&pop_waker))
+        .is_pending());
+    RING.try_push(2).unwrap();
+    assert_eq!(count.get(), 3);
+    assert_eq!(not_empty.as_mut().poll(&mut cx), Poll::Ready(()));
+    assert_eq!(pop_count.get(), 1);
+    assert_eq!(pop.as_mut().poll(&mut cx), Poll::Ready(2));
+}
+
+#[test]
+fn peek_threads() {
+    const COUNT: usize = 10000;
+    static RING: Ring<usize, 4, 1, 1> = Ring::new();
+    static DONE: AtomicBool = AtomicBool::new(false);
+    let peeker = std::thread::spawn(|| {
+        let mut last = 0;
+        while !DONE.load(SeqCst) {
+            if let Ok(x) = RING.peek() {
+                assert!(x >= last);
+                last = x;
+            }
+        }
+    });
+    let producer = std::thread::spawn(|| {
+        for i in 0..COUNT {
+            while RING.try_push(i).is_err() {
+                std::thread::yield_now();
+            }
+        }
+    });
+    for i in 0..COUNT {
+        loop {
+            match RING.try_pop() {
+                Ok(x) => {
+                    assert_eq!(x, i);
+                    break;
+                }
+                Err(_) => std::thread::yield_now(),
+            }
+        }
+    }
+    DONE.store(true, SeqCst);
+    producer.join().unwrap();
+    peeker.join().unwrap();
+}
@@ -0,0 +1,41 @@
+use async_ach_notify::{Notify, PriorityNotify};
+use async_ach_ring::Ring;
+use core::future::Future;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static RING: Ring<usize, 2, 2, 2, Notify<2>, PriorityNotify<2>> = Ring::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    let mut low = Box::pin(RING.pop_with_priority(0));
+    let mut high = Box::pin(RING.pop_with_priority(1));
+    assert!(low.as_mut().poll(&mut cx).is_pending());
+    assert!(high.as_mut().poll(&mut cx).is_pending());
+
+    RING.try_push(1).unwrap();
+    assert_eq!(count.get(), 1);
+    // the woken consumer is polled first
+    assert_eq!(high.as_mut().poll(&mut cx), Poll::Ready(1));
+    RING.try_push(2).unwrap();
+    assert_eq!(count.get(), 2);
+    assert_eq!(low.as_mut().poll(&mut cx), Poll::Ready(2));
+}
+
+#[test]
+fn ignored() {
+    // `Notify` wakes any parked consumer
+    static RING: Ring<usize, 2, 2, 2> = Ring::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+
+    let mut low = Box::pin(RING.pop_with_priority(0));
+    let mut high = Box::pin(RING.pop_with_priority(1));
+    assert!(low.as_mut().poll(&mut cx).is_pending());
+    assert!(high.as_mut().poll(&mut cx).is_pending());
+
+    RING.try_push(1).unwrap();
+    assert_eq!(count.get(), 1);
+}
@@ -0,0 +1,139 @@
+#![no_std]
+
+use async_ach_notify::{Listener, Notify};
+use core::future::Future;
+use core::pin::Pin;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst};
+use core::task::{Context, Poll};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryAcquireError {
+    /// The semaphore has been closed.
+    Closed,
+    /// There are not enough permits available.
+    NoPermits,
+}
+
+/// The semaphore has been closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcquireError;
+
+pub struct Permit<'a, const W: usize> {
+    parent: &'a Semaphore<W>,
+    permits: usize,
+}
+impl<'a, const W: usize> Permit<'a, W> {
+    /// Returns the number of permits held by this guard.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+    /// Forgets the permits without releasing them back to the semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+}
+impl<'a, const W: usize> Drop for Permit<'a, W> {
+    fn drop(&mut self) {
+        if self.permits != 0 {
+            self.parent.add_permits(self.permits);
+        }
+    }
+}
+
+/// Counting semaphore.
+///
+/// `W` is the number of waiters which can be parked at the same time.
+/// Waiters are not served in FIFO order.
+pub struct Semaphore<const W: usize> {
+    permits: AtomicUsize,
+    closed: AtomicBool,
+    waiters: Notify<W>,
+}
+impl<const W: usize> Semaphore<W> {
+    pub const fn new(permits: usize) -> Self {
+        Self {
+            permits: AtomicUsize::new(permits),
+            closed: AtomicBool::new(false),
+            waiters: Notify::new(),
+        }
+    }
+    /// Returns the current number of available permits.
+    pub fn available_permits(&self) -> usize {
+        self.permits.load(SeqCst)
+    }
+    /// Adds `n` new permits to the semaphore, and wake all waiters.
+    pub fn add_permits(&self, n: usize) {
+        self.permits.fetch_add(n, SeqCst);
+        self.waiters.notify_waiters();
+    }
+    /// Closes the semaphore, and wake all waiters.
+    ///
+    /// Pending and later acquires will return Err.
+    pub fn close(&self) {
+        self.closed.store(true, SeqCst);
+        self.waiters.notify_waiters();
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(SeqCst)
+    }
+    /// Tries to acquire a permit.
+    ///
+    /// Returns Err if the semaphore is closed or has no permits.
+    pub fn try_acquire(&self) -> Result<Permit<'_, W>, TryAcquireError> {
+        self.try_acquire_many(1)
+    }
+    /// Tries to acquire `n` permits.
+    ///
+    /// Returns Err if the semaphore is closed or has not enough permits.
+    pub fn try_acquire_many(&self, n: usize) -> Result<Permit<'_, W>, TryAcquireError> {
+        if self.is_closed() {
+            return Err(TryAcquireError::Closed);
+        }
+        self.permits
+            .fetch_update(SeqCst, SeqCst, |x| x.checked_sub(n))
+            .map(|_| Permit {
+                parent: self,
+                permits: n,
+            })
+            .map_err(|_| TryAcquireError::NoPermits)
+    }
+    /// Acquires `n` permits.
+    ///
+    /// Returns Err if the semaphore is closed.
+    pub fn acquire(&self, n: usize) -> Acquire<'_, W> {
+        Acquire {
+            parent: self,
+            permits: n,
+            wait: self.waiters.listen(),
+        }
+    }
+}
+
+pub struct Acquire<'a, const W: usize> {
+    parent: &'a Semaphore<W>,
+    permits: usize,
+    wait: Listener<'a, W>,
+}
+impl<'a, const W: usize> Acquire<'a, W> {
+    fn try_poll(&self) -> Poll<Result<Permit<'a, W>, AcquireError>> {
+        match self.parent.try_acquire_many(self.permits) {
+            Ok(permit) => Poll::Ready(Ok(permit)),
+            Err(TryAcquireError::Closed) => Poll::Ready(Err(AcquireError)),
+            Err(TryAcquireError::NoPermits) => Poll::Pending,
+        }
+    }
+}
+impl<'a, const W: usize> Future for Acquire<'a, W> {
+    type Output = Result<Permit<'a, W>, AcquireError>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            if let Poll::Ready(ret) = self.try_poll() {
+                return Poll::Ready(ret);
+            }
+            if Pin::new(&mut self.wait).poll(cx).is_pending() {
+                // permits may be released before the waker registered
+                return self.try_poll();
+            }
+        }
+    }
+}
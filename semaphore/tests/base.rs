@@ -0,0 +1,42 @@
+use async_ach_semaphore::{AcquireError, Semaphore, TryAcquireError};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static SEM: Semaphore<2> = Semaphore::new(2);
+    let mut cx = task::noop_context();
+
+    let permit1 = SEM.try_acquire().unwrap();
+    let mut acquire1 = SEM.acquire(2);
+    assert!(Pin::new(&mut acquire1).poll(&mut cx).is_pending());
+    let mut acquire2 = SEM.acquire(1);
+    let permit2 = match Pin::new(&mut acquire2).poll(&mut cx) {
+        Poll::Ready(Ok(permit)) => permit,
+        _ => panic!(),
+    };
+    assert_eq!(SEM.available_permits(), 0);
+    assert_eq!(SEM.try_acquire().err(), Some(TryAcquireError::NoPermits));
+    drop(permit1);
+    assert!(Pin::new(&mut acquire1).poll(&mut cx).is_pending());
+    drop(permit2);
+    let permit3 = match Pin::new(&mut acquire1).poll(&mut cx) {
+        Poll::Ready(Ok(permit)) => permit,
+        _ => panic!(),
+    };
+    assert_eq!(permit3.num_permits(), 2);
+    permit3.forget();
+    assert_eq!(SEM.available_permits(), 0);
+
+    let mut acquire3 = SEM.acquire(1);
+    assert!(Pin::new(&mut acquire3).poll(&mut cx).is_pending());
+    SEM.close();
+    assert_eq!(
+        Pin::new(&mut acquire3).poll(&mut cx).map(|x| x.err()),
+        Poll::Ready(Some(AcquireError))
+    );
+    SEM.add_permits(1);
+    assert_eq!(SEM.try_acquire().err(), Some(TryAcquireError::Closed));
+}
@@ -0,0 +1,168 @@
+use crate::atomic::{AtomicU32, AtomicUsize, Ordering::SeqCst};
+use ach_util::{MemoryRing, MemoryState};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+struct Slot<T> {
+    val: UnsafeCell<MaybeUninit<T>>,
+    /// `MemoryRing` of the cycle of the index
+    op: AtomicU32,
+}
+impl<T> Slot<T> {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const INIT: Self = Self {
+        val: UnsafeCell::new(MaybeUninit::uninit()),
+        // `MemoryRing::INIT`
+        op: AtomicU32::new(0),
+    };
+}
+
+/// The buffer of `Spsc`, which lends its slots to the sender and the receiver.
+///
+/// A slot is written in place after `reserve` and published by `commit`,
+/// and read in place after `claim` until `release`.
+/// The sender only moves the end, the receiver moves the start, or the sender evicting in overwrite mode.
+pub(crate) struct SlotBuffer<T, const N: usize> {
+    slots: [Slot<T>; N],
+    /// always points to the first element
+    start: AtomicUsize,
+    end: AtomicUsize,
+}
+unsafe impl<T: Send, const N: usize> Send for SlotBuffer<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for SlotBuffer<T, N> {}
+impl<T, const N: usize> SlotBuffer<T, N> {
+    const WRAP_MAX: usize = MemoryRing::max_idx(N);
+    pub const fn new() -> Self {
+        Self {
+            slots: [Slot::INIT; N],
+            start: AtomicUsize::new(0),
+            end: AtomicUsize::new(0),
+        }
+    }
+    fn wrap_len(start: usize, end: usize) -> usize {
+        if end >= start {
+            end - start
+        } else {
+            Self::WRAP_MAX - start + end
+        }
+    }
+    fn next_idx(old: usize) -> usize {
+        if old == Self::WRAP_MAX - 1 {
+            0
+        } else {
+            old + 1
+        }
+    }
+    fn op(idx: usize, state: MemoryState) -> u32 {
+        MemoryRing::new(MemoryRing::cycle_of_idx(idx, N), state).into()
+    }
+    fn slot(&self, idx: usize) -> &Slot<T> {
+        &self.slots[idx % N]
+    }
+    /// Returns the pointer of the slot at `idx`.
+    pub fn ptr(&self, idx: usize) -> *mut T {
+        self.slot(idx).val.get() as *mut T
+    }
+
+    /// Returns the index of the free slot at the end, it is published by `commit`.
+    ///
+    /// Returns None if the buffer is full, or the receiver still holds the slot.
+    /// Only the sender calls it.
+    pub fn reserve(&self) -> Option<usize> {
+        let end = self.end.load(SeqCst);
+        let op = self.slot(end).op.load(SeqCst);
+        if op == Self::op(end, MemoryState::Uninitialized) {
+            Some(end)
+        } else {
+            None
+        }
+    }
+    /// Publishes the slot written after `reserve`.
+    pub fn commit(&self, idx: usize) {
+        let slot = self.slot(idx);
+        slot.op
+            .store(Self::op(idx, MemoryState::Initialized), SeqCst);
+        self.end.store(Self::next_idx(idx), SeqCst);
+    }
+    /// Returns the index of the first element, and moves the start past it.
+    /// The slot is held by the receiver until `release`.
+    ///
+    /// Returns None if the buffer is empty, or the sender is evicting the first element.
+    /// Only the receiver calls it, `evictable` must be the same as the sender's.
+    pub fn claim(&self, evictable: bool) -> Option<usize> {
+        loop {
+            let start = self.start.load(SeqCst);
+            let slot = self.slot(start);
+            let initialized = Self::op(start, MemoryState::Initialized);
+            let claimed = Self::op(start, MemoryState::Erasing);
+            if !evictable {
+                // no one else touches the first element
+                if slot.op.load(SeqCst) != initialized {
+                    return None;
+                }
+                slot.op.store(claimed, SeqCst);
+                self.start.store(Self::next_idx(start), SeqCst);
+                return Some(start);
+            }
+            match slot
+                .op
+                .compare_exchange(initialized, claimed, SeqCst, SeqCst)
+            {
+                Ok(_) => {
+                    // the sender may have moved it already
+                    let _ =
+                        self.start
+                            .compare_exchange(start, Self::next_idx(start), SeqCst, SeqCst);
+                    return Some(start);
+                }
+                // evicted by the sender, which has not moved the start yet
+                Err(op) if op == claimed => {
+                    let _ =
+                        self.start
+                            .compare_exchange(start, Self::next_idx(start), SeqCst, SeqCst);
+                }
+                Err(_) => return None,
+            }
+        }
+    }
+    /// Frees the slot returned by `claim`, the value must be moved out or dropped.
+    pub fn release(&self, idx: usize) {
+        let cycle = MemoryRing::cycle_of_idx(idx, N) + 1;
+        let op = MemoryRing::new(cycle, MemoryState::Uninitialized);
+        self.slot(idx).op.store(op.into(), SeqCst);
+    }
+    /// Takes the first element out if the buffer is full, so the sender can reserve its slot.
+    ///
+    /// Returns None if the buffer is not full, or the receiver is receiving the first element.
+    /// Only the sender calls it in overwrite mode.
+    pub fn evict(&self) -> Option<T> {
+        let end = self.end.load(SeqCst);
+        let start = self.start.load(SeqCst);
+        if Self::wrap_len(start, end) < N {
+            return None;
+        }
+        let slot = self.slot(start);
+        let initialized = Self::op(start, MemoryState::Initialized);
+        let claimed = Self::op(start, MemoryState::Erasing);
+        slot.op
+            .compare_exchange(initialized, claimed, SeqCst, SeqCst)
+            .ok()?;
+        let _ = self
+            .start
+            .compare_exchange(start, Self::next_idx(start), SeqCst, SeqCst);
+        let val = unsafe { ptr::read(self.ptr(start)) };
+        self.release(start);
+        Some(val)
+    }
+}
+impl<T, const N: usize> Drop for SlotBuffer<T, N> {
+    fn drop(&mut self) {
+        for slot in self.slots.iter_mut() {
+            let op = MemoryRing::from(*slot.op.get_mut());
+            if op.state().is_initialized() {
+                unsafe { slot.val.get_mut().assume_init_drop() };
+            }
+        }
+    }
+}
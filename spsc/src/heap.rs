@@ -1,32 +1,89 @@
 use crate::heapless::Spsc;
 use alloc::sync::Arc;
+use async_ach_ring::heap::Ring;
 
+/// The sending side of a channel created by `channel`, it holds the role of the sender until dropped.
 pub struct Sender<T: Unpin, const N: usize> {
-    tx: Arc<Spsc<T, N>>,
+    spsc: Arc<Spsc<T, N>>,
 }
 impl<T: Unpin, const N: usize> Sender<T, N> {
     pub fn try_send(&mut self, val: T) -> Result<(), T> {
-        self.tx.take_sender().unwrap().try_send(val)
+        self.spsc.try_send(val)
     }
     pub async fn send(&mut self, val: T) {
-        self.tx.take_sender().unwrap().send(val).await
+        self.spsc.send(val).await
+    }
+}
+impl<T: Unpin, const N: usize> Drop for Sender<T, N> {
+    fn drop(&mut self) {
+        self.spsc.release_sender_role();
     }
 }
 
+/// The receiving side of a channel created by `channel`, it holds the role of the receiver until dropped.
 pub struct Receiver<T: Unpin, const N: usize> {
-    rx: Arc<Spsc<T, N>>,
+    spsc: Arc<Spsc<T, N>>,
 }
 impl<T: Unpin, const N: usize> Receiver<T, N> {
     pub fn try_recv(&mut self) -> Option<T> {
-        self.rx.take_recver().unwrap().try_recv()
+        self.spsc.try_recv()
     }
     pub async fn recv(&mut self) -> T {
-        self.rx.take_recver().unwrap().recv().await
+        self.spsc.recv().await
+    }
+}
+impl<T: Unpin, const N: usize> Drop for Receiver<T, N> {
+    fn drop(&mut self) {
+        self.spsc.release_recver_role();
     }
 }
 
+/// Creates a Spsc shared by an Arc.
+///
+/// The endpoints take their role of the Spsc once, so each operation costs no more than the heapless ones.
 pub fn channel<T: Unpin, const N: usize>() -> (Sender<T, N>, Receiver<T, N>) {
-    let tx = Arc::new(Spsc::new());
+    let spsc = Arc::new(Spsc::new());
+    // the Spsc is new, so both roles are free
+    assert!(spsc.take_sender_role() && spsc.take_recver_role());
+    (Sender { spsc: spsc.clone() }, Receiver { spsc })
+}
+
+/// The sending side of a channel created by `bounded`.
+pub struct BoundedSender<T: Unpin> {
+    tx: Arc<Ring<T, 1, 1>>,
+}
+impl<T: Unpin> BoundedSender<T> {
+    pub fn try_send(&mut self, val: T) -> Result<(), T> {
+        self.tx.try_push(val).map_err(|err| err.input)
+    }
+    pub async fn send(&mut self, val: T) {
+        self.tx.push(val).await
+    }
+}
+
+/// The receiving side of a channel created by `bounded`.
+pub struct BoundedReceiver<T: Unpin> {
+    rx: Arc<Ring<T, 1, 1>>,
+}
+impl<T: Unpin> BoundedReceiver<T> {
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.rx.try_pop().ok()
+    }
+    pub async fn recv(&mut self) -> T {
+        self.rx.pop().await
+    }
+}
+
+/// Creates a channel with the capacity chosen at runtime.
+///
+/// The buffer of `Spsc` is an array, so the channel is a `heap::Ring` of `async_ach_ring`
+/// with a single producer and consumer, and the endpoints are `BoundedSender` and `BoundedReceiver`.
+/// They send and receive like `Sender` and `Receiver`, but each operation costs the CAS of the Ring,
+/// and there is no overwrite mode.
+///
+/// Panics if `capacity` is 0.
+pub fn bounded<T: Unpin>(capacity: usize) -> (BoundedSender<T>, BoundedReceiver<T>) {
+    let tx = Arc::new(Ring::with_capacity(capacity));
     let rx = tx.clone();
-    (Sender { tx }, Receiver { rx })
+    (BoundedSender { tx }, BoundedReceiver { rx })
 }
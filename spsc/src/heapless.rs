@@ -1,74 +1,180 @@
-use ach_spsc as ach;
-use async_ach_notify::Notify;
-use futures_util::StreamExt;
+use crate::atomic::{AtomicBool, AtomicUsize, Ordering::SeqCst};
+use crate::buffer::SlotBuffer;
+use async_ach_notify::{Notifier, Notify};
+use core::mem::ManuallyDrop;
+use core::ops::{Deref, DerefMut};
+use core::ptr;
+use futures_util::{pin_mut, StreamExt};
 
-pub struct Spsc<T, const N: usize> {
-    buf: ach::Spsc<T, N>,
-    consumer: Notify<1>,
-    producer: Notify<1>,
+/// `B` is the notify backend of both sides, e.g. `IntrusiveNotify`.
+pub struct Spsc<T, const N: usize, B = Notify<1>> {
+    buf: SlotBuffer<T, N>,
+    pub(crate) consumer: B,
+    pub(crate) producer: B,
+    /// the sender evicts the oldest element if full
+    overwrite: bool,
+    sender_taken: AtomicBool,
+    recver_taken: AtomicBool,
+    /// number of evicted elements
+    overflowed: AtomicUsize,
 }
-impl<T, const N: usize> Spsc<T, N> {
+impl<T, const N: usize, B: Notifier> Default for Spsc<T, N, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T, const N: usize, B: Notifier> Spsc<T, N, B> {
     pub const fn new() -> Self {
+        Self::with_overwrite(false)
+    }
+    /// Creates a Spsc in which sending evicts the oldest element if full, instead of failing or waiting.
+    ///
+    /// `force_send` returns the evicted element, `try_send` and `send` drop it.
+    ///
+    /// Notice: the sender and the receiver race for the oldest element,
+    /// so each receive costs a CAS.
+    pub const fn new_overwrite() -> Self {
+        Self::with_overwrite(true)
+    }
+    const fn with_overwrite(overwrite: bool) -> Self {
         Self {
-            buf: ach::Spsc::new(),
-            consumer: Notify::new(),
-            producer: Notify::new(),
+            buf: SlotBuffer::new(),
+            consumer: B::NEW,
+            producer: B::NEW,
+            overwrite,
+            sender_taken: AtomicBool::new(false),
+            recver_taken: AtomicBool::new(false),
+            overflowed: AtomicUsize::new(0),
         }
     }
+    pub const fn is_overwrite(&self) -> bool {
+        self.overwrite
+    }
 }
-impl<T: Unpin, const N: usize> Spsc<T, N> {
-    pub fn take_sender(&self) -> Option<Sender<T, N>> {
-        let sender = self.buf.take_sender()?;
-        Some(Sender {
-            parent: self,
-            sender,
-        })
+impl<T: Unpin, const N: usize, B: Notifier> Spsc<T, N, B> {
+    /// Returns None if the sender is taken, it can be taken again after dropped.
+    pub fn take_sender(&self) -> Option<Sender<'_, T, N, B>> {
+        if self.take_sender_role() {
+            Some(Sender { parent: self })
+        } else {
+            None
+        }
     }
-    pub fn take_recver(&self) -> Option<Receiver<T, N>> {
-        let recver = self.buf.take_recver()?;
-        Some(Receiver {
-            parent: self,
-            recver,
-        })
+    /// Returns None if the receiver is taken, it can be taken again after dropped.
+    pub fn take_recver(&self) -> Option<Receiver<'_, T, N, B>> {
+        if self.take_recver_role() {
+            Some(Receiver { parent: self })
+        } else {
+            None
+        }
     }
 }
 
-pub struct Sender<'a, T: Unpin, const N: usize> {
-    parent: &'a Spsc<T, N>,
-    sender: ach::Sender<'a, T, N>,
-}
-impl<'a, T: Unpin, const N: usize> Sender<'a, T, N> {
-    pub fn try_send(&mut self, val: T) -> Result<(), T> {
-        self.sender.try_send(val).map(|_| {
-            self.parent.producer.notify_one();
-        })
+// The operations of the sender and the receiver,
+// they are only called by the holder of the role.
+impl<T, const N: usize, B: Notifier> Spsc<T, N, B> {
+    /// Returns false if the sender is taken.
+    pub(crate) fn take_sender_role(&self) -> bool {
+        self.sender_taken
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .is_ok()
     }
-    pub async fn send<'b>(&'b mut self, mut val: T) {
-        let mut wait_c = self.parent.consumer.listen();
-        loop {
-            if let Err(v) = self.try_send(val) {
-                val = v;
-                wait_c.next().await;
-            } else {
-                break;
+    pub(crate) fn release_sender_role(&self) {
+        self.sender_taken.store(false, SeqCst);
+    }
+    /// Returns false if the receiver is taken.
+    pub(crate) fn take_recver_role(&self) -> bool {
+        self.recver_taken
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .is_ok()
+    }
+    pub(crate) fn release_recver_role(&self) {
+        self.recver_taken.store(false, SeqCst);
+    }
+    /// Sends without notifying the receiver.
+    pub(crate) fn push(&self, val: T) -> Result<(), T> {
+        match self.buf.reserve() {
+            Some(idx) => {
+                unsafe { ptr::write(self.buf.ptr(idx), val) };
+                self.buf.commit(idx);
+                Ok(())
             }
+            None => Err(val),
         }
     }
-}
-
-pub struct Receiver<'a, T, const N: usize> {
-    parent: &'a Spsc<T, N>,
-    recver: ach::Receiver<'a, T, N>,
-}
-impl<'a, T: Unpin, const N: usize> Receiver<'a, T, N> {
-    pub fn try_recv(&mut self) -> Option<T> {
-        self.recver.try_recv().map(|v| {
-            self.parent.consumer.notify_one();
-            v
+    /// Receives without notifying the sender.
+    pub(crate) fn pop(&self) -> Option<T> {
+        let idx = self.buf.claim(self.overwrite)?;
+        let val = unsafe { ptr::read(self.buf.ptr(idx)) };
+        self.buf.release(idx);
+        Some(val)
+    }
+    fn evict(&self) -> Option<T> {
+        let evicted = self.buf.evict();
+        if evicted.is_some() {
+            self.overflowed.fetch_add(1, SeqCst);
+        }
+        evicted
+    }
+    pub(crate) fn try_send(&self, val: T) -> Result<(), T> {
+        if self.overwrite {
+            self.force_send(val).map(drop)
+        } else {
+            self.push(val).map(|_| self.producer.notify_one())
+        }
+    }
+    pub(crate) fn force_send(&self, val: T) -> Result<Option<T>, T> {
+        let val = match self.push(val) {
+            Ok(()) => {
+                self.producer.notify_one();
+                return Ok(None);
+            }
+            Err(val) if self.overwrite => val,
+            Err(val) => return Err(val),
+        };
+        let evicted = self.evict();
+        // the receiver may free a slot meanwhile
+        self.push(val).map(|_| {
+            self.producer.notify_one();
+            evicted
         })
     }
-    pub async fn recv<'b>(&'b mut self) -> T {
-        let mut wait_p = self.parent.producer.listen();
+    pub(crate) async fn send(&self, mut val: T) {
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_c);
+        while let Err(v) = self.try_send(val) {
+            val = v;
+            wait_c.next().await;
+        }
+    }
+    pub(crate) async fn reserve(&self) -> SlotMut<'_, T, N, B>
+    where
+        T: Default,
+    {
+        let wait_c = self.consumer.listen();
+        pin_mut!(wait_c);
+        let idx = loop {
+            if let Some(idx) = self.buf.reserve() {
+                break idx;
+            }
+            if self.overwrite {
+                // the evicted element is dropped, like `try_send`
+                drop(self.evict());
+                if let Some(idx) = self.buf.reserve() {
+                    break idx;
+                }
+            }
+            wait_c.next().await;
+        };
+        unsafe { ptr::write(self.buf.ptr(idx), T::default()) };
+        SlotMut { parent: self, idx }
+    }
+    pub(crate) fn try_recv(&self) -> Option<T> {
+        self.pop().inspect(|_| self.consumer.notify_one())
+    }
+    pub(crate) async fn recv(&self) -> T {
+        let wait_p = self.producer.listen();
+        pin_mut!(wait_p);
         loop {
             if let Some(v) = self.try_recv() {
                 break v;
@@ -77,4 +183,132 @@ impl<'a, T: Unpin, const N: usize> Receiver<'a, T, N> {
             }
         }
     }
+    pub(crate) async fn recv_ref(&self) -> SlotRef<'_, T, N, B> {
+        let wait_p = self.producer.listen();
+        pin_mut!(wait_p);
+        loop {
+            if let Some(idx) = self.buf.claim(self.overwrite) {
+                break SlotRef { parent: self, idx };
+            } else {
+                wait_p.next().await;
+            }
+        }
+    }
+    pub(crate) fn take_overflowed(&self) -> usize {
+        self.overflowed.swap(0, SeqCst)
+    }
+}
+
+pub struct Sender<'a, T: Unpin, const N: usize, B: Notifier = Notify<1>> {
+    parent: &'a Spsc<T, N, B>,
+}
+impl<'a, T: Unpin, const N: usize, B: Notifier> Drop for Sender<'a, T, N, B> {
+    fn drop(&mut self) {
+        self.parent.release_sender_role();
+    }
+}
+impl<'a, T: Unpin, const N: usize, B: Notifier> Sender<'a, T, N, B> {
+    /// Returns Err if the Spsc is full.
+    /// In overwrite mode, the oldest element is dropped if full, see `force_send`.
+    pub fn try_send(&mut self, val: T) -> Result<(), T> {
+        self.parent.try_send(val)
+    }
+    /// Sends the value, evicts the oldest element and returns it if full.
+    ///
+    /// Returns Err if it is full out of overwrite mode,
+    /// or the receiver is receiving the oldest element.
+    pub fn force_send(&mut self, val: T) -> Result<Option<T>, T> {
+        self.parent.force_send(val)
+    }
+    pub async fn send(&mut self, val: T) {
+        self.parent.send(val).await
+    }
+    /// Waits for a free slot, which is written in place and sent when the `SlotMut` is dropped.
+    ///
+    /// The slot is filled with `T::default()` first.
+    /// In overwrite mode, the oldest element is dropped if full.
+    pub async fn reserve(&mut self) -> SlotMut<'_, T, N, B>
+    where
+        T: Default,
+    {
+        self.parent.reserve().await
+    }
+}
+
+/// A slot of the Spsc reserved by `Sender::reserve`, which is sent on drop.
+pub struct SlotMut<'a, T, const N: usize, B: Notifier = Notify<1>> {
+    parent: &'a Spsc<T, N, B>,
+    idx: usize,
+}
+impl<'a, T, const N: usize, B: Notifier> SlotMut<'a, T, N, B> {
+    /// Frees the slot without sending, and returns the value.
+    pub fn cancel(self) -> T {
+        let this = ManuallyDrop::new(self);
+        // the slot is not published, so it is still free
+        unsafe { ptr::read(this.parent.buf.ptr(this.idx)) }
+    }
+}
+impl<'a, T, const N: usize, B: Notifier> Deref for SlotMut<'a, T, N, B> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.parent.buf.ptr(self.idx) }
+    }
+}
+impl<'a, T, const N: usize, B: Notifier> DerefMut for SlotMut<'a, T, N, B> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.parent.buf.ptr(self.idx) }
+    }
+}
+impl<'a, T, const N: usize, B: Notifier> Drop for SlotMut<'a, T, N, B> {
+    fn drop(&mut self) {
+        self.parent.buf.commit(self.idx);
+        self.parent.producer.notify_one();
+    }
+}
+
+pub struct Receiver<'a, T, const N: usize, B: Notifier = Notify<1>> {
+    parent: &'a Spsc<T, N, B>,
+}
+impl<'a, T, const N: usize, B: Notifier> Drop for Receiver<'a, T, N, B> {
+    fn drop(&mut self) {
+        self.parent.release_recver_role();
+    }
+}
+impl<'a, T: Unpin, const N: usize, B: Notifier> Receiver<'a, T, N, B> {
+    /// Returns the number of elements evicted by the sender, and resets it.
+    pub fn take_overflowed(&self) -> usize {
+        self.parent.take_overflowed()
+    }
+    /// Returns None if the Spsc is empty, or the sender is evicting in overwrite mode.
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.parent.try_recv()
+    }
+    pub async fn recv(&mut self) -> T {
+        self.parent.recv().await
+    }
+    /// Waits for an element, which is read in place and freed when the `SlotRef` is dropped.
+    ///
+    /// The sender can not reuse the slot until then, even in overwrite mode.
+    pub async fn recv_ref(&mut self) -> SlotRef<'_, T, N, B> {
+        self.parent.recv_ref().await
+    }
+}
+
+/// An element of the Spsc received by `Receiver::recv_ref`, which is dropped in place on drop.
+pub struct SlotRef<'a, T, const N: usize, B: Notifier = Notify<1>> {
+    parent: &'a Spsc<T, N, B>,
+    idx: usize,
+}
+impl<'a, T, const N: usize, B: Notifier> Deref for SlotRef<'a, T, N, B> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        unsafe { &*self.parent.buf.ptr(self.idx) }
+    }
+}
+impl<'a, T, const N: usize, B: Notifier> Drop for SlotRef<'a, T, N, B> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.parent.buf.ptr(self.idx)) };
+        self.parent.buf.release(self.idx);
+        self.parent.consumer.notify_one();
+    }
 }
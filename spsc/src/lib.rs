@@ -1,7 +1,20 @@
 #![no_std]
 #[cfg(feature = "alloc")]
 extern crate alloc;
+#[cfg(feature = "futures-io")]
+extern crate std;
 
+mod buffer;
 #[cfg(feature = "alloc")]
 pub mod heap;
 pub mod heapless;
+pub mod pipe;
+
+pub use heapless::Spsc;
+
+#[cfg(loom)]
+use async_ach_notify::loom_atomic as atomic;
+#[cfg(all(not(loom), feature = "critical-section"))]
+use atomic_polyfill as atomic;
+#[cfg(all(not(loom), not(feature = "critical-section")))]
+use core::sync::atomic;
@@ -0,0 +1,247 @@
+use crate::atomic::{AtomicBool, Ordering::SeqCst};
+use crate::heapless::{Receiver, Sender, Spsc};
+use async_ach_notify::Listener;
+use core::fmt;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_util::future::poll_fn;
+use futures_util::Stream;
+
+/// The other side of the pipe is closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Closed;
+impl fmt::Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("pipe closed")
+    }
+}
+
+/// A byte stream on top of `Spsc<u8, N>`.
+///
+/// The pipe is closed once the writer or the reader is dropped, and it is not reopened,
+/// so neither side can be taken again.
+pub struct Pipe<const N: usize> {
+    spsc: Spsc<u8, N>,
+    closed: AtomicBool,
+}
+impl<const N: usize> Default for Pipe<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> Pipe<N> {
+    pub const fn new() -> Self {
+        Self {
+            spsc: Spsc::new(),
+            closed: AtomicBool::new(false),
+        }
+    }
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(SeqCst)
+    }
+    fn close(&self) {
+        if !self.closed.swap(true, SeqCst) {
+            self.spsc.producer.notify_one();
+            self.spsc.consumer.notify_one();
+        }
+    }
+    pub fn take_writer(&self) -> Option<Writer<'_, N>> {
+        if self.is_closed() {
+            return None;
+        }
+        let sender = self.spsc.take_sender()?;
+        Some(Writer {
+            parent: self,
+            _sender: sender,
+            wait_c: self.spsc.consumer.listen(),
+        })
+    }
+    pub fn take_reader(&self) -> Option<Reader<'_, N>> {
+        if self.is_closed() {
+            return None;
+        }
+        let recver = self.spsc.take_recver()?;
+        Some(Reader {
+            parent: self,
+            _recver: recver,
+            wait_p: self.spsc.producer.listen(),
+        })
+    }
+}
+
+pub struct Writer<'a, const N: usize> {
+    parent: &'a Pipe<N>,
+    /// holds the role of the sender
+    _sender: Sender<'a, u8, N>,
+    wait_c: Listener<'a, 1>,
+}
+impl<'a, const N: usize> Writer<'a, N> {
+    /// Writes as many bytes as there is space for, and returns the number of written bytes.
+    pub fn try_write(&mut self, buf: &[u8]) -> Result<usize, Closed> {
+        if self.parent.is_closed() {
+            return Err(Closed);
+        }
+        let mut len = 0;
+        for &byte in buf {
+            if self.parent.spsc.push(byte).is_err() {
+                break;
+            }
+            len += 1;
+        }
+        if len != 0 {
+            self.parent.spsc.producer.notify_one();
+        }
+        Ok(len)
+    }
+    /// Waits until at least one byte is written, or `buf` is empty.
+    pub fn poll_write(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<Result<usize, Closed>> {
+        loop {
+            match self.try_write(buf) {
+                Ok(0) if !buf.is_empty() => {}
+                ret => return Poll::Ready(ret),
+            }
+            if Pin::new(&mut self.wait_c).poll_next(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+    pub async fn write(&mut self, buf: &[u8]) -> Result<usize, Closed> {
+        poll_fn(|cx| self.poll_write(cx, buf)).await
+    }
+    pub async fn write_all(&mut self, mut buf: &[u8]) -> Result<(), Closed> {
+        while !buf.is_empty() {
+            let len = self.write(buf).await?;
+            buf = &buf[len..];
+        }
+        Ok(())
+    }
+    /// Closes the pipe, the reader reads the remaining bytes and then the end of stream.
+    pub fn close(&mut self) {
+        self.parent.close();
+    }
+}
+impl<'a, const N: usize> Drop for Writer<'a, N> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+pub struct Reader<'a, const N: usize> {
+    parent: &'a Pipe<N>,
+    /// holds the role of the receiver
+    _recver: Receiver<'a, u8, N>,
+    wait_p: Listener<'a, 1>,
+}
+impl<'a, const N: usize> Reader<'a, N> {
+    /// Reads the available bytes, and returns the number of read bytes.
+    pub fn try_read(&mut self, buf: &mut [u8]) -> usize {
+        let mut len = 0;
+        for byte in buf.iter_mut() {
+            match self.parent.spsc.pop() {
+                Some(b) => *byte = b,
+                None => break,
+            }
+            len += 1;
+        }
+        if len != 0 {
+            self.parent.spsc.consumer.notify_one();
+        }
+        len
+    }
+    /// Waits until at least one byte is read, `buf` is empty or the pipe is closed.
+    ///
+    /// Returns 0 at the end of stream.
+    pub fn poll_read(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<usize> {
+        loop {
+            // the writer closes after its last write
+            let closed = self.parent.is_closed();
+            let len = self.try_read(buf);
+            if len != 0 || buf.is_empty() || closed {
+                return Poll::Ready(len);
+            }
+            if Pin::new(&mut self.wait_p).poll_next(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+    }
+    pub async fn read(&mut self, buf: &mut [u8]) -> usize {
+        poll_fn(|cx| self.poll_read(cx, buf)).await
+    }
+    /// Closes the pipe, the writer fails to write later.
+    pub fn close(&mut self) {
+        self.parent.close();
+    }
+}
+impl<'a, const N: usize> Drop for Reader<'a, N> {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(feature = "futures-io")]
+mod futures_io_impl {
+    use super::{Reader, Writer};
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use std::io;
+
+    impl<'a, const N: usize> futures_io::AsyncRead for Reader<'a, N> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<io::Result<usize>> {
+            Reader::poll_read(self.get_mut(), cx, buf).map(Ok)
+        }
+    }
+    impl<'a, const N: usize> futures_io::AsyncWrite for Writer<'a, N> {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Writer::poll_write(self.get_mut(), cx, buf)
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            self.get_mut().close();
+            Poll::Ready(Ok(()))
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io-async")]
+mod embedded_io_impl {
+    use super::{Closed, Reader, Writer};
+    use core::convert::Infallible;
+    use embedded_io_async::{ErrorKind, ErrorType, Read, Write};
+
+    impl embedded_io_async::Error for Closed {
+        fn kind(&self) -> ErrorKind {
+            ErrorKind::BrokenPipe
+        }
+    }
+
+    impl<'a, const N: usize> ErrorType for Reader<'a, N> {
+        type Error = Infallible;
+    }
+    impl<'a, const N: usize> Read for Reader<'a, N> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Infallible> {
+            Ok(Reader::read(self, buf).await)
+        }
+    }
+    impl<'a, const N: usize> ErrorType for Writer<'a, N> {
+        type Error = Closed;
+    }
+    impl<'a, const N: usize> Write for Writer<'a, N> {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Closed> {
+            Writer::write(self, buf).await
+        }
+    }
+}
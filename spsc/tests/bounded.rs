@@ -0,0 +1,33 @@
+#![cfg(feature = "alloc")]
+
+use async_ach_spsc::heap::bounded;
+use futures_executor::ThreadPool;
+
+#[test]
+fn test() {
+    let (mut sender, mut recver) = bounded::<usize>(3);
+    for i in 0..3 {
+        sender.try_send(i).unwrap();
+    }
+    assert_eq!(sender.try_send(3), Err(3));
+    for i in 0..3 {
+        assert_eq!(recver.try_recv(), Some(i));
+    }
+    assert_eq!(recver.try_recv(), None);
+}
+
+#[test]
+fn spsc() {
+    let (mut sender, mut recver) = bounded::<usize>(2);
+    let executor = ThreadPool::new().unwrap();
+    executor.spawn_ok(async move {
+        for i in 0..10000 {
+            sender.send(i).await;
+        }
+    });
+    futures_executor::block_on(async {
+        for i in 0..10000 {
+            assert_eq!(recver.recv().await, i);
+        }
+    });
+}
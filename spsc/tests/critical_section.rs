@@ -0,0 +1,31 @@
+#![cfg(feature = "critical-section")]
+
+use async_ach_spsc::Spsc;
+use core::future::Future;
+use core::task::Poll;
+use futures_test::task;
+use std::thread;
+
+#[test]
+fn test() {
+    static SPSC: Spsc<usize, 2> = Spsc::new();
+    let mut cx = task::noop_context();
+
+    let mut recver = SPSC.take_recver().unwrap();
+    let mut recv = Box::pin(recver.recv());
+    assert!(recv.as_mut().poll(&mut cx).is_pending());
+    // send from an "interrupt"
+    thread::spawn(|| {
+        let mut sender = SPSC.take_sender().unwrap();
+        critical_section::with(|_| {
+            sender.try_send(1).unwrap();
+            sender.try_send(2).unwrap();
+            assert!(sender.try_send(3).is_err());
+        })
+    })
+    .join()
+    .unwrap();
+    assert_eq!(recv.as_mut().poll(&mut cx), Poll::Ready(1));
+    drop(recv);
+    critical_section::with(|_| assert_eq!(recver.try_recv(), Some(2)));
+}
@@ -0,0 +1,45 @@
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=3 cargo test --test loom --release`.
+#![cfg(loom)]
+
+use async_ach_spsc::Spsc;
+use loom::future::block_on;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn send_recv() {
+    loom::model(|| {
+        let spsc: Arc<Spsc<usize, 1>> = Arc::new(Spsc::new());
+
+        let producer = spsc.clone();
+        let th = thread::spawn(move || {
+            let mut sender = producer.take_sender().unwrap();
+            block_on(sender.send(1));
+            block_on(sender.send(2));
+        });
+        let mut recver = spsc.take_recver().unwrap();
+        assert_eq!(block_on(recver.recv()), 1);
+        assert_eq!(block_on(recver.recv()), 2);
+        th.join().unwrap();
+    });
+}
+
+#[test]
+fn reserve_recv_ref() {
+    loom::model(|| {
+        let spsc: Arc<Spsc<usize, 1>> = Arc::new(Spsc::new());
+
+        let producer = spsc.clone();
+        let th = thread::spawn(move || {
+            let mut sender = producer.take_sender().unwrap();
+            for i in 1..3 {
+                *block_on(sender.reserve()) = i;
+            }
+        });
+        let mut recver = spsc.take_recver().unwrap();
+        for i in 1..3 {
+            assert_eq!(*block_on(recver.recv_ref()), i);
+        }
+        th.join().unwrap();
+    });
+}
@@ -0,0 +1,67 @@
+use async_ach_spsc::heapless::Spsc;
+
+#[test]
+fn test() {
+    static SPSC: Spsc<usize, 2> = Spsc::new_overwrite();
+    let mut sender = SPSC.take_sender().unwrap();
+    let mut recver = SPSC.take_recver().unwrap();
+    assert!(SPSC.take_recver().is_none());
+
+    sender.try_send(1).unwrap();
+    sender.try_send(2).unwrap();
+    sender.try_send(3).unwrap();
+    assert_eq!(sender.force_send(4), Ok(Some(2)));
+    assert_eq!(recver.take_overflowed(), 2);
+    assert_eq!(recver.take_overflowed(), 0);
+    assert_eq!(recver.try_recv(), Some(3));
+    assert_eq!(recver.try_recv(), Some(4));
+    assert_eq!(recver.try_recv(), None);
+
+    drop(recver);
+    assert!(SPSC.take_recver().is_some());
+}
+
+#[test]
+fn not_overwrite() {
+    static SPSC: Spsc<usize, 1> = Spsc::new();
+    let mut sender = SPSC.take_sender().unwrap();
+    let mut recver = SPSC.take_recver().unwrap();
+    sender.try_send(1).unwrap();
+    assert_eq!(sender.try_send(2), Err(2));
+    // the receiver holds the receiving end
+    assert_eq!(sender.force_send(2), Err(2));
+    assert_eq!(recver.try_recv(), Some(1));
+}
+
+#[test]
+fn threads() {
+    const COUNT: usize = 100000;
+    static SPSC: Spsc<usize, 2> = Spsc::new_overwrite();
+    let mut sender = SPSC.take_sender().unwrap();
+    let mut recver = SPSC.take_recver().unwrap();
+
+    let th = std::thread::spawn(move || {
+        for i in 0..COUNT {
+            // fails while the receiver is receiving
+            while sender.try_send(i).is_err() {
+                std::thread::yield_now();
+            }
+        }
+    });
+    let mut received = 0;
+    let mut last = None;
+    loop {
+        let done = th.is_finished();
+        while let Some(v) = recver.try_recv() {
+            assert!(last < Some(v));
+            last = Some(v);
+            received += 1;
+        }
+        if done {
+            break;
+        }
+    }
+    th.join().unwrap();
+    assert_eq!(last, Some(COUNT - 1));
+    assert_eq!(received + recver.take_overflowed(), COUNT);
+}
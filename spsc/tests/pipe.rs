@@ -0,0 +1,59 @@
+use async_ach_spsc::pipe::{Closed, Pipe};
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static PIPE: Pipe<4> = Pipe::new();
+    let mut cx = task::noop_context();
+    let mut writer = PIPE.take_writer().unwrap();
+    assert!(PIPE.take_writer().is_none());
+    let mut reader = PIPE.take_reader().unwrap();
+    assert!(PIPE.take_reader().is_none());
+
+    assert_eq!(writer.try_write(b"hello"), Ok(4));
+    assert_eq!(writer.try_write(b"o"), Ok(0));
+    let mut buf = [0; 3];
+    assert_eq!(reader.try_read(&mut buf), 3);
+    assert_eq!(&buf, b"hel");
+
+    let mut write = Box::pin(writer.write_all(b"o world"));
+    assert!(Pin::new(&mut write).poll(&mut cx).is_pending());
+    let mut buf = [0; 8];
+    assert_eq!(reader.try_read(&mut buf), 4);
+    assert_eq!(&buf[..4], b"lo w");
+    assert_eq!(Pin::new(&mut write).poll(&mut cx), Poll::Ready(Ok(())));
+    drop(write);
+
+    drop(writer);
+    assert!(PIPE.is_closed());
+    assert!(PIPE.take_writer().is_none());
+    let mut read = Box::pin(reader.read(&mut buf));
+    assert_eq!(Pin::new(&mut read).poll(&mut cx), Poll::Ready(4));
+    drop(read);
+    assert_eq!(&buf[..4], b"orld");
+    let mut read = Box::pin(reader.read(&mut buf));
+    assert_eq!(Pin::new(&mut read).poll(&mut cx), Poll::Ready(0));
+}
+
+#[test]
+fn wake() {
+    static PIPE: Pipe<2> = Pipe::new();
+    let (waker, count) = task::new_count_waker();
+    let mut cx = core::task::Context::from_waker(&waker);
+    let mut writer = PIPE.take_writer().unwrap();
+    let mut reader = PIPE.take_reader().unwrap();
+
+    let mut buf = [0; 2];
+    assert!(reader.poll_read(&mut cx, &mut buf).is_pending());
+    assert_eq!(writer.try_write(b"a"), Ok(1));
+    assert_eq!(count.get(), 1);
+    assert_eq!(reader.poll_read(&mut cx, &mut buf), Poll::Ready(1));
+
+    assert!(reader.poll_read(&mut cx, &mut buf).is_pending());
+    drop(reader);
+    assert_eq!(count.get(), 2);
+    assert_eq!(writer.try_write(b"b"), Err(Closed));
+}
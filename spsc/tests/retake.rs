@@ -0,0 +1,67 @@
+use async_ach_spsc::heapless::Spsc;
+
+#[test]
+fn test() {
+    static SPSC: Spsc<usize, 2> = Spsc::new();
+    let mut sender = SPSC.take_sender().unwrap();
+    let mut recver = SPSC.take_recver().unwrap();
+    assert!(SPSC.take_sender().is_none());
+    assert!(SPSC.take_recver().is_none());
+    sender.try_send(1).unwrap();
+    drop(sender);
+    drop(recver);
+
+    let mut sender = SPSC.take_sender().unwrap();
+    recver = SPSC.take_recver().unwrap();
+    sender.try_send(2).unwrap();
+    assert_eq!(recver.try_recv(), Some(1));
+    assert_eq!(recver.try_recv(), Some(2));
+}
+
+#[test]
+fn overwrite() {
+    static SPSC: Spsc<usize, 2> = Spsc::new_overwrite();
+    drop(SPSC.take_recver().unwrap());
+    let mut recver = SPSC.take_recver().unwrap();
+    drop(SPSC.take_sender().unwrap());
+    SPSC.take_sender().unwrap().try_send(1).unwrap();
+    assert_eq!(recver.try_recv(), Some(1));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn heap() {
+    let (mut sender, mut recver) = async_ach_spsc::heap::channel::<usize, 2>();
+    sender.try_send(1).unwrap();
+    sender.try_send(2).unwrap();
+    assert_eq!(sender.try_send(3), Err(3));
+    assert_eq!(recver.try_recv(), Some(1));
+    assert_eq!(recver.try_recv(), Some(2));
+    assert_eq!(recver.try_recv(), None);
+    futures_executor::block_on(sender.send(3));
+    assert_eq!(futures_executor::block_on(recver.recv()), 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn heap_borrowed() {
+    let val = String::from("borrowed");
+    let (mut sender, mut recver) = async_ach_spsc::heap::channel::<&str, 1>();
+    sender.try_send(&val).unwrap();
+    assert_eq!(recver.try_recv(), Some("borrowed"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn heap_threads() {
+    let (mut sender, mut recver) = async_ach_spsc::heap::channel::<usize, 2>();
+    let th = std::thread::spawn(move || {
+        for i in 0..10000 {
+            futures_executor::block_on(sender.send(i));
+        }
+    });
+    for i in 0..10000 {
+        assert_eq!(futures_executor::block_on(recver.recv()), i);
+    }
+    th.join().unwrap();
+}
@@ -0,0 +1,76 @@
+use async_ach_spsc::heapless::Spsc;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::Poll;
+use futures_test::task;
+
+#[test]
+fn test() {
+    static SPSC: Spsc<usize, 1> = Spsc::new();
+    let mut cx = task::noop_context();
+    let mut sender = SPSC.take_sender().unwrap();
+    let mut recver = SPSC.take_recver().unwrap();
+
+    let mut reserve = Box::pin(sender.reserve());
+    let mut slot = match Pin::new(&mut reserve).poll(&mut cx) {
+        Poll::Ready(slot) => slot,
+        Poll::Pending => panic!("reserve is pending"),
+    };
+    *slot = 1;
+    drop(slot);
+    drop(reserve);
+    assert_eq!(sender.try_send(2), Err(2));
+
+    let (wake, count) = task::new_count_waker();
+    let mut cx_s = core::task::Context::from_waker(&wake);
+    let mut reserve = Box::pin(sender.reserve());
+    assert!(Pin::new(&mut reserve).poll(&mut cx_s).is_pending());
+
+    let mut recv = Box::pin(recver.recv_ref());
+    let slot = match Pin::new(&mut recv).poll(&mut cx) {
+        Poll::Ready(slot) => slot,
+        Poll::Pending => panic!("recv_ref is pending"),
+    };
+    assert_eq!(*slot, 1);
+    assert_eq!(count.get(), 0);
+    drop(slot);
+    assert_eq!(count.get(), 1);
+
+    match Pin::new(&mut reserve).poll(&mut cx_s) {
+        Poll::Ready(slot) => slot.cancel(),
+        Poll::Pending => panic!("reserve is pending"),
+    };
+    drop(reserve);
+    drop(recv);
+    assert_eq!(recver.try_recv(), None);
+}
+
+#[test]
+fn overwrite() {
+    static SPSC: Spsc<usize, 1> = Spsc::new_overwrite();
+    let mut cx = task::noop_context();
+    let mut sender = SPSC.take_sender().unwrap();
+    let mut recver = SPSC.take_recver().unwrap();
+
+    for i in 1..4 {
+        let mut reserve = Box::pin(sender.reserve());
+        match Pin::new(&mut reserve).poll(&mut cx) {
+            Poll::Ready(mut slot) => *slot = i,
+            Poll::Pending => panic!("reserve is pending"),
+        };
+    }
+    assert_eq!(recver.take_overflowed(), 2);
+
+    let mut recv = Box::pin(recver.recv_ref());
+    let slot = match Pin::new(&mut recv).poll(&mut cx) {
+        Poll::Ready(slot) => slot,
+        Poll::Pending => panic!("recv_ref is pending"),
+    };
+    // the slot held by the receiver is not evicted
+    assert_eq!(sender.force_send(4), Err(4));
+    assert_eq!(*slot, 3);
+    drop(slot);
+    assert_eq!(sender.force_send(4), Ok(None));
+    drop(recv);
+    assert_eq!(recver.try_recv(), Some(4));
+}
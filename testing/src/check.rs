@@ -0,0 +1,76 @@
+//! Asserts that futures register and wake correctly.
+
+use crate::Executor;
+use async_ach_notify::Notifier;
+use async_ach_ring::Ring;
+use async_ach_watch::Watch;
+use std::fmt::Debug;
+use std::future::Future;
+
+/// Polls `fut` once, runs `trigger`, then asserts that the task is woken exactly once and ready.
+///
+/// # Panics
+///
+/// Panics if `fut` is ready on the first poll, or wakes itself, which means it is not registered.
+#[track_caller]
+pub fn assert_wakeup<F: Future>(fut: F, trigger: impl FnOnce()) -> F::Output {
+    let mut exec = Executor::new();
+    let task = exec.spawn(fut);
+    assert!(!exec.poll(task.id()), "ready before triggered");
+    exec.assert_woken(&[]);
+    trigger();
+    exec.assert_woken(&[task.id()]);
+    assert!(exec.poll(task.id()), "pending after woken");
+    task.take_output().unwrap()
+}
+
+/// Asserts that `notify_one` wakes exactly one of two parked listeners, and `notify_all` wakes the other.
+///
+/// The notifier must hold 2 waiters at least.
+#[track_caller]
+pub fn check_notifier<N: Notifier>(notify: &N) {
+    let mut exec = Executor::new();
+    let a = exec.spawn(notify.listen());
+    let b = exec.spawn(notify.listen());
+    exec.run_until_stalled();
+    exec.assert_woken(&[]);
+
+    notify.notify_one();
+    let woken = exec.take_woken();
+    assert_eq!(woken.len(), 1, "notify_one wakes {} listeners", woken.len());
+    assert!(exec.poll(woken[0]), "pending after woken");
+
+    let other = if woken[0] == a.id() { b.id() } else { a.id() };
+    notify.notify_all();
+    exec.assert_woken(&[other]);
+    assert!(exec.poll(other), "pending after woken");
+}
+
+/// Asserts that `Ring::pop` is parked on an empty ring, and woken by `try_push`.
+#[track_caller]
+pub fn check_ring_pop<T, const N: usize, const MP: usize, const MC: usize, C, P>(
+    ring: &Ring<T, N, MP, MC, C, P>,
+    val: T,
+) where
+    T: Unpin + Clone + PartialEq + Debug,
+    C: Notifier,
+    P: Notifier,
+{
+    assert_eq!(ring.len(), 0, "the ring is not empty");
+    let out = assert_wakeup(ring.pop(), || ring.try_push(val.clone()).unwrap());
+    assert_eq!(out, val);
+}
+
+/// Asserts that `Changed` is parked until the value is sent, and woken by `try_send`.
+#[track_caller]
+pub fn check_changed<T, const W: usize, P>(watch: &Watch<T, W, P>, val: T)
+where
+    T: Unpin + Clone + PartialEq + Debug,
+    P: Notifier,
+{
+    let mut rx = watch.subscribe();
+    let out = assert_wakeup(rx.changed(), || {
+        assert!(watch.try_send(val.clone()).is_ok(), "the watch is busy")
+    });
+    assert_eq!(out, val);
+}
@@ -0,0 +1,30 @@
+//! The clock of `async-tick`, driven by hand.
+//!
+//! The clock is global, so the tests of a binary share it, and it only moves forward.
+//! It can not be used with `async_tick::auto_tick`.
+
+use async_tick::Tick;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+static TICK: OnceLock<Mutex<Tick>> = OnceLock::new();
+
+/// Moves the clock forward, and wakes the expired `Sleep`s.
+///
+/// Returns the time in nanoseconds.
+///
+/// # Panics
+///
+/// Panics if the clock is driven by others, e.g. `async_tick::auto_tick`.
+pub fn advance(duration: Duration) -> u64 {
+    let tick = TICK.get_or_init(|| {
+        Mutex::new(async_tick::take_tick().expect("the clock is driven by others"))
+    });
+    let mut tick = tick.lock().unwrap_or_else(|e| e.into_inner());
+    tick.tick(duration)
+}
+
+/// Returns the time in nanoseconds.
+pub fn now() -> u64 {
+    async_tick::now()
+}
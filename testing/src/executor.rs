@@ -0,0 +1,186 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// Polls without a wakeup before `run_until_stalled` gives up.
+const MAX_POLLS: usize = 10000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TaskId(usize);
+
+struct TaskWaker {
+    id: TaskId,
+    wakes: Arc<Mutex<Vec<TaskId>>>,
+}
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wakes.lock().unwrap().push(self.id);
+    }
+}
+
+struct Slot<'a> {
+    fut: Option<Pin<Box<dyn Future<Output = ()> + 'a>>>,
+    waker: Waker,
+    polls: usize,
+    wakes: usize,
+}
+
+/// Handle of a spawned future.
+pub struct Task<T> {
+    id: TaskId,
+    output: Rc<RefCell<Option<T>>>,
+}
+impl<T> Task<T> {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+    /// Returns true if the output is ready, and not taken.
+    pub fn is_ready(&self) -> bool {
+        self.output.borrow().is_some()
+    }
+    pub fn take_output(&self) -> Option<T> {
+        self.output.borrow_mut().take()
+    }
+}
+
+/// A single threaded executor, which runs only when asked.
+///
+/// Tasks are polled in the order of the wakeups, and every wakeup is logged until `take_woken`.
+/// The futures may borrow from the stack, as `Executor` is not `'static`.
+pub struct Executor<'a> {
+    tasks: Vec<Slot<'a>>,
+    /// wakeups not handled by the executor, may be sent from other threads
+    wakes: Arc<Mutex<Vec<TaskId>>>,
+    /// wakeups not taken by `take_woken`
+    woken: Vec<TaskId>,
+    queue: VecDeque<TaskId>,
+}
+impl<'a> Default for Executor<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<'a> Executor<'a> {
+    pub fn new() -> Self {
+        Self {
+            tasks: Vec::new(),
+            wakes: Arc::new(Mutex::new(Vec::new())),
+            woken: Vec::new(),
+            queue: VecDeque::new(),
+        }
+    }
+    /// Adds a task, which is scheduled but not polled.
+    pub fn spawn<F: Future + 'a>(&mut self, fut: F) -> Task<F::Output> {
+        let id = TaskId(self.tasks.len());
+        let output = Rc::new(RefCell::new(None));
+        let out = output.clone();
+        let fut = async move {
+            let val = fut.await;
+            *out.borrow_mut() = Some(val);
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            wakes: self.wakes.clone(),
+        }));
+        self.tasks.push(Slot {
+            fut: Some(Box::pin(fut)),
+            waker,
+            polls: 0,
+            wakes: 0,
+        });
+        self.queue.push_back(id);
+        Task { id, output }
+    }
+    /// Polls the task once, even if it is not woken.
+    ///
+    /// Returns true if the task is finished.
+    pub fn poll(&mut self, id: TaskId) -> bool {
+        self.sync();
+        self.queue.retain(|x| *x != id);
+        let slot = &mut self.tasks[id.0];
+        if let Some(fut) = &mut slot.fut {
+            slot.polls += 1;
+            let mut cx = Context::from_waker(&slot.waker);
+            if let Poll::Ready(()) = fut.as_mut().poll(&mut cx) {
+                slot.fut = None;
+            }
+        }
+        self.sync();
+        slot_finished(&self.tasks[id.0])
+    }
+    /// Polls the first woken task.
+    ///
+    /// Returns None if no task is woken.
+    pub fn step(&mut self) -> Option<TaskId> {
+        self.sync();
+        while let Some(id) = self.queue.pop_front() {
+            if !slot_finished(&self.tasks[id.0]) {
+                self.poll(id);
+                return Some(id);
+            }
+        }
+        None
+    }
+    /// Polls the woken tasks until none is woken.
+    ///
+    /// Returns the number of polls.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tasks keep waking up, e.g. a future wakes itself on every poll.
+    pub fn run_until_stalled(&mut self) -> usize {
+        let mut polls = 0;
+        while self.step().is_some() {
+            polls += 1;
+            assert!(polls < MAX_POLLS, "the tasks never stall");
+        }
+        polls
+    }
+    pub fn is_finished(&self, id: TaskId) -> bool {
+        slot_finished(&self.tasks[id.0])
+    }
+    /// Returns the number of times the task has been polled.
+    pub fn poll_count(&self, id: TaskId) -> usize {
+        self.tasks[id.0].polls
+    }
+    /// Returns the number of times the task has been woken.
+    pub fn wake_count(&mut self, id: TaskId) -> usize {
+        self.sync();
+        self.tasks[id.0].wakes
+    }
+    /// Returns the wakeups since the last call, in order.
+    pub fn take_woken(&mut self) -> Vec<TaskId> {
+        self.sync();
+        core::mem::take(&mut self.woken)
+    }
+    /// Asserts that the wakeups since the last `take_woken` are `ids`, ignoring the order.
+    #[track_caller]
+    pub fn assert_woken(&mut self, ids: &[TaskId]) {
+        let mut woken = self.take_woken();
+        let mut ids = ids.to_vec();
+        woken.sort();
+        ids.sort();
+        assert_eq!(woken, ids, "unexpected wakeups");
+    }
+    fn sync(&mut self) {
+        let wakes = core::mem::take(&mut *self.wakes.lock().unwrap());
+        for id in wakes {
+            self.tasks[id.0].wakes += 1;
+            self.woken.push(id);
+            if !self.queue.contains(&id) {
+                self.queue.push_back(id);
+            }
+        }
+    }
+}
+
+fn slot_finished(slot: &Slot<'_>) -> bool {
+    slot.fut.is_none()
+}
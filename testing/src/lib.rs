@@ -0,0 +1,10 @@
+//! Deterministic tests for code built on async_ach.
+//!
+//! `Executor` polls the tasks step by step on the current thread, and records every wakeup,
+//! so a test can assert e.g. that exactly one task is woken by `notify_one`.
+
+pub mod check;
+pub mod clock;
+mod executor;
+
+pub use executor::{Executor, Task, TaskId};
@@ -0,0 +1,45 @@
+use async_ach_notify::{IntrusiveNotify, Notify};
+use async_ach_ring::Ring;
+use async_ach_testing::{check, Executor};
+use async_ach_watch::Watch;
+
+#[test]
+fn notifier() {
+    check::check_notifier(&Notify::<2>::new());
+    check::check_notifier(&IntrusiveNotify::new());
+}
+
+#[test]
+fn ring_pop() {
+    let ring: Ring<usize, 2, 1, 1> = Ring::new();
+    check::check_ring_pop(&ring, 1);
+    check::check_ring_pop(&ring, 2);
+}
+
+#[test]
+fn changed() {
+    let watch: Watch<usize, 1> = Watch::new(0);
+    check::check_changed(&watch, 1);
+}
+
+#[test]
+fn executor() {
+    let notify = Notify::<2>::new();
+    let mut exec = Executor::new();
+    let a = exec.spawn(notify.listen());
+    let b = exec.spawn(async {
+        notify.listen().await;
+        1
+    });
+    assert_eq!(exec.step(), Some(a.id()));
+    assert_eq!(exec.step(), Some(b.id()));
+    assert_eq!(exec.step(), None);
+
+    notify.notify_waiters();
+    exec.assert_woken(&[a.id(), b.id()]);
+    assert_eq!(exec.run_until_stalled(), 2);
+    assert!(exec.is_finished(a.id()));
+    assert_eq!(b.take_output(), Some(1));
+    assert_eq!(exec.poll_count(b.id()), 2);
+    assert_eq!(exec.wake_count(b.id()), 1);
+}
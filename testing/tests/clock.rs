@@ -0,0 +1,17 @@
+use async_ach_testing::{clock, Executor};
+use core::time::Duration;
+
+#[test]
+fn sleep() {
+    let mut exec = Executor::new();
+    let task = exec.spawn(async_tick::sleep(Duration::from_millis(10)));
+    exec.run_until_stalled();
+    exec.assert_woken(&[]);
+
+    clock::advance(Duration::from_millis(5));
+    exec.assert_woken(&[]);
+    clock::advance(Duration::from_millis(6));
+    exec.assert_woken(&[task.id()]);
+    exec.run_until_stalled();
+    assert!(exec.is_finished(task.id()));
+}
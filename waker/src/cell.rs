@@ -0,0 +1,298 @@
+//! The cells of `ach-cell` and `ach-option` on the atomics of this crate.
+//!
+//! Their state words are `crate::atomic`, so they are emulated by `atomic-polyfill`
+//! on targets without CAS, and loom may switch threads at each of them.
+//!
+//! Like the `ach-*` ones, every operation which leaves a transient state runs in
+//! an `interrupt::CriticalSection`, so an interrupt never sees the transient state
+//! of the code it preempted on a single core.
+
+use crate::atomic::{AtomicU32, AtomicU8, Ordering::SeqCst};
+use crate::spin_loop;
+use ach_util::{Error, MemoryState};
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+use core::ptr;
+use interrupt::CriticalSection;
+
+/// Retries `f` while it returns a transient error.
+fn retry<I, O>(mut f: impl FnMut(I) -> Result<O, Error<I>>, mut input: I) -> Result<O, Error<I>> {
+    loop {
+        match f(input) {
+            Err(err) if err.retry => {
+                input = err.input;
+                spin_loop();
+            }
+            ret => return ret,
+        }
+    }
+}
+
+/// The state of `Cell` in the high byte, and the number of `Ref` in the others.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Refer(u32);
+impl Refer {
+    const REFS: u32 = 0x00FF_FFFF;
+    const fn new(state: MemoryState) -> Self {
+        Self((state as u32) << 24)
+    }
+    fn state(self) -> MemoryState {
+        ((self.0 >> 24) as u8).into()
+    }
+    fn with_state(self, state: MemoryState) -> Self {
+        Self((self.0 & Self::REFS) | ((state as u32) << 24))
+    }
+    fn refs(self) -> u32 {
+        self.0 & Self::REFS
+    }
+    /// Returns Err if the cell is not initialized, or the value is being taken.
+    fn error<I>(self, input: I) -> Error<I> {
+        let state = self.state();
+        if state.is_initialized() {
+            // refered
+            Error {
+                state: MemoryState::Regaining,
+                input,
+                retry: true,
+            }
+        } else {
+            Error {
+                state,
+                input,
+                retry: state.is_transient(),
+            }
+        }
+    }
+}
+
+/// A shared value, which is taken or replaced after all `Ref` are dropped.
+pub(crate) struct Cell<T> {
+    val: UnsafeCell<MaybeUninit<T>>,
+    state: AtomicU32,
+}
+unsafe impl<T: Send> Send for Cell<T> {}
+unsafe impl<T: Send + Sync> Sync for Cell<T> {}
+impl<T> Cell<T> {
+    pub const fn new() -> Self {
+        Self {
+            val: UnsafeCell::new(MaybeUninit::uninit()),
+            state: AtomicU32::new(Refer::new(MemoryState::Uninitialized).0),
+        }
+    }
+    fn store(&self, state: MemoryState) {
+        self.state.store(Refer::new(state).0, SeqCst);
+    }
+    fn update(&self, mut f: impl FnMut(Refer) -> Option<Refer>) -> Result<Refer, Refer> {
+        self.state
+            .fetch_update(SeqCst, SeqCst, |x| f(Refer(x)).map(|x| x.0))
+            .map(Refer)
+            .map_err(Refer)
+    }
+    /// Tries to get a reference to the value.
+    ///
+    /// Returns Err if the cell is uninitialized, or the value is being set, taken or removed.
+    pub fn try_get(&self) -> Result<Ref<'_, T>, Error<()>> {
+        match self.update(|x| {
+            if x.state().is_initialized() && x.refs() < Refer::REFS {
+                Some(Refer(x.0 + 1))
+            } else {
+                None
+            }
+        }) {
+            Ok(_) => Ok(Ref(self)),
+            Err(x) => {
+                let state = x.state();
+                Err(Error {
+                    state,
+                    input: (),
+                    retry: state.is_initializing(),
+                })
+            }
+        }
+    }
+    /// Gets a reference to the value, spins while it is being set.
+    pub fn get(&self) -> Result<Ref<'_, T>, Error<()>> {
+        retry(|_| self.try_get(), ())
+    }
+    /// Takes the value out, leaving the cell uninitialized.
+    ///
+    /// Returns Err if the value is refered, or being set or taken.
+    pub fn try_take(&self) -> Result<Option<T>, Error<()>> {
+        let _cs = CriticalSection::new();
+        match self.update(|x| {
+            if x.state().is_initialized() && x.refs() == 0 {
+                Some(x.with_state(MemoryState::Erasing))
+            } else {
+                None
+            }
+        }) {
+            Ok(_) => {
+                let ret = unsafe { ptr::read(self.val.get()).assume_init() };
+                self.store(MemoryState::Uninitialized);
+                Ok(Some(ret))
+            }
+            Err(x) if x.state().is_uninitialized() => Ok(None),
+            Err(x) => Err(x.error(())),
+        }
+    }
+    /// Replaces the value, and returns the old one.
+    ///
+    /// Returns Err if the value is refered, or being set or taken.
+    pub fn try_replace(&self, value: T) -> Result<Option<T>, Error<T>> {
+        let _cs = CriticalSection::new();
+        match self.update(|x| {
+            let state = x.state();
+            if state.is_uninitialized() || (state.is_initialized() && x.refs() == 0) {
+                Some(x.with_state(MemoryState::Initializing))
+            } else {
+                None
+            }
+        }) {
+            Ok(old) => {
+                let ret = if old.state().is_initialized() {
+                    Some(unsafe { ptr::read(self.val.get()).assume_init() })
+                } else {
+                    None
+                };
+                unsafe { ptr::write(self.val.get(), MaybeUninit::new(value)) };
+                self.store(MemoryState::Initialized);
+                Ok(ret)
+            }
+            Err(x) => Err(x.error(value)),
+        }
+    }
+}
+impl<T> Drop for Cell<T> {
+    fn drop(&mut self) {
+        let state = Refer(*self.state.get_mut()).state();
+        if state.is_initialized() || state.is_erasing() {
+            unsafe { self.val.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// A reference to the value of `Cell`.
+pub(crate) struct Ref<'a, T>(&'a Cell<T>);
+impl<'a, T> Ref<'a, T> {
+    /// Removes the value from the cell, it is dropped with the last `Ref`.
+    pub fn remove(&self) {
+        let _ = self.0.update(|x| {
+            if x.state().is_initialized() {
+                Some(x.with_state(MemoryState::Erasing))
+            } else {
+                None
+            }
+        });
+    }
+}
+impl<'a, T> Deref for Ref<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        unsafe { (*self.0.val.get()).assume_init_ref() }
+    }
+}
+impl<'a, T> Drop for Ref<'a, T> {
+    fn drop(&mut self) {
+        let _cs = CriticalSection::new();
+        let old = match self.0.update(|x| Some(Refer(x.0 - 1))) {
+            Ok(x) | Err(x) => x,
+        };
+        if old.refs() == 1 && old.state().is_erasing() {
+            unsafe { (*self.0.val.get()).assume_init_drop() };
+            self.0.store(MemoryState::Uninitialized);
+        }
+    }
+}
+
+/// An optional value, which is only moved in and out.
+pub(crate) struct AtomicOption<T> {
+    val: UnsafeCell<MaybeUninit<T>>,
+    state: AtomicU8,
+}
+unsafe impl<T: Send> Send for AtomicOption<T> {}
+unsafe impl<T: Send> Sync for AtomicOption<T> {}
+impl<T> AtomicOption<T> {
+    pub const fn new_with(init: T) -> Self {
+        Self {
+            val: UnsafeCell::new(MaybeUninit::new(init)),
+            state: AtomicU8::new(MemoryState::Initialized as u8),
+        }
+    }
+    pub fn is_none(&self) -> bool {
+        MemoryState::from(self.state.load(SeqCst)).is_uninitialized()
+    }
+    /// Returns Err if the value is being set or taken.
+    pub fn try_take(&self) -> Result<Option<T>, Error<()>> {
+        let _cs = CriticalSection::new();
+        match self.state.compare_exchange(
+            MemoryState::Initialized as u8,
+            MemoryState::Erasing as u8,
+            SeqCst,
+            SeqCst,
+        ) {
+            Ok(_) => {
+                let ret = unsafe { ptr::read(self.val.get()).assume_init() };
+                self.state.store(MemoryState::Uninitialized as u8, SeqCst);
+                Ok(Some(ret))
+            }
+            Err(state) => match MemoryState::from(state) {
+                MemoryState::Uninitialized => Ok(None),
+                state => Err(Error {
+                    state,
+                    input: (),
+                    retry: state.is_transient(),
+                }),
+            },
+        }
+    }
+    /// Takes the value out, spins while it is being set or taken.
+    pub fn take(&self) -> Option<T> {
+        retry(|_| self.try_take(), ()).unwrap_or(None)
+    }
+    /// Returns Err if the value is being set or taken.
+    pub fn try_replace(&self, value: T) -> Result<Option<T>, Error<T>> {
+        let _cs = CriticalSection::new();
+        match self.state.fetch_update(SeqCst, SeqCst, |x| {
+            let state = MemoryState::from(x);
+            if state.is_uninitialized() || state.is_initialized() {
+                Some(MemoryState::Initializing as u8)
+            } else {
+                None
+            }
+        }) {
+            Ok(old) => {
+                let ret = if MemoryState::from(old).is_initialized() {
+                    Some(unsafe { ptr::read(self.val.get()).assume_init() })
+                } else {
+                    None
+                };
+                unsafe { ptr::write(self.val.get(), MaybeUninit::new(value)) };
+                self.state.store(MemoryState::Initialized as u8, SeqCst);
+                Ok(ret)
+            }
+            Err(state) => {
+                let state = MemoryState::from(state);
+                Err(Error {
+                    state,
+                    input: value,
+                    retry: state.is_transient(),
+                })
+            }
+        }
+    }
+    /// Replaces the value, spins while it is being set or taken.
+    pub fn replace(&self, value: T) -> Option<T> {
+        match retry(|v| self.try_replace(v), value) {
+            Ok(old) => old,
+            Err(_) => unreachable!(),
+        }
+    }
+}
+impl<T> Drop for AtomicOption<T> {
+    fn drop(&mut self) {
+        if MemoryState::from(*self.state.get_mut()).is_initialized() {
+            unsafe { self.val.get_mut().assume_init_drop() };
+        }
+    }
+}
@@ -1,22 +1,26 @@
-use ach_option::AchOption;
+use crate::cell::AtomicOption;
 use core::ops::Deref;
 use core::task::Waker;
 
 pub struct WakerEntity<T> {
-    waker: AchOption<Waker>,
+    waker: AtomicOption<Waker>,
     val: T,
 }
 impl<T> WakerEntity<T> {
     pub fn new(waker: Waker, val: T) -> WakerEntity<T> {
-        let waker = AchOption::new_with(waker);
+        let waker = AtomicOption::new_with(waker);
         WakerEntity { waker, val }
     }
     pub fn set_waker(&self, waker: Waker) {
         self.waker.replace(waker);
     }
+    /// Take the waker out, then it is waked.
+    pub fn take_waker(&self) -> Option<Waker> {
+        self.waker.take()
+    }
     /// return true if wake it success.
     pub fn wake(&self) -> bool {
-        if let Some(waker) = self.waker.take() {
+        if let Some(waker) = self.take_waker() {
             waker.wake();
             true
         } else {
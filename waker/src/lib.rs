@@ -1,7 +1,26 @@
 #![no_std]
 
+#[cfg(loom)]
+#[doc(hidden)]
+pub mod loom_atomic;
+
+#[cfg(all(not(loom), feature = "critical-section"))]
+use atomic_polyfill as atomic;
+#[cfg(not(loom))]
+use core::hint::spin_loop;
+#[cfg(all(not(loom), not(feature = "critical-section")))]
+use core::sync::atomic;
+#[cfg(loom)]
+use loom_atomic as atomic;
+#[cfg(loom)]
+use loom_atomic::spin_loop;
+
+mod cell;
 mod entity;
+#[cfg(target_has_atomic = "ptr")]
 pub mod list;
 pub mod pool;
+#[cfg(target_has_atomic = "ptr")]
+pub mod sync_list;
 
 pub use entity::WakerEntity;
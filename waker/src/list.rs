@@ -1,26 +1,50 @@
 //! Only work on single thread
+//!
+//! Use `sync_list::SyncWakerList` to share it between threads.
 
 use crate::WakerEntity;
 use ach_linked::LinkedList;
 pub use ach_linked::Node;
+use core::cell::Cell;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
 
 pub struct WakerList<T> {
     list: LinkedList<WakerEntity<T>>,
+    _not_sync: PhantomData<Cell<()>>,
+}
+impl<T> Default for WakerList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 impl<T> WakerList<T> {
     pub const fn new() -> Self {
         Self {
             list: LinkedList::new(),
+            _not_sync: PhantomData,
         }
     }
     /// Register a waker
     ///
-    /// Safety:
+    /// # Safety
     /// This function is only safe as long as `node` is guaranteed to
     /// get removed from the list before it gets moved or dropped.
     pub unsafe fn register(&self, waker: &mut Node<WakerEntity<T>>) {
         self.list.push(waker)
     }
+    /// Wait for being waked by `wake_one` or `wake_all`.
+    ///
+    /// The node is stored in the returned future, and removed from the list on drop.
+    pub fn listen(&self, val: T) -> Listen<'_, T> {
+        Listen {
+            parent: self,
+            val: Some(val),
+            node: None,
+        }
+    }
     /// Removes a node from the LinkedList.
     pub fn remove(&self, waker: &mut Node<WakerEntity<T>>) {
         self.list.remove(waker)
@@ -93,3 +117,38 @@ impl<T> WakerList<T> {
         }
     }
 }
+
+pub struct Listen<'a, T> {
+    parent: &'a WakerList<T>,
+    val: Option<T>,
+    node: Option<Node<WakerEntity<T>>>,
+}
+impl<'a, T> Future for Listen<'a, T> {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Safety: the node is never moved, and it is removed from the list on drop.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let Some(node) = &this.node {
+            if node.is_waked() {
+                Poll::Ready(())
+            } else {
+                node.set_waker(cx.waker().clone());
+                Poll::Pending
+            }
+        } else {
+            let val = this.val.take().expect("resumed after completion");
+            let node = this
+                .node
+                .insert(Node::new(WakerEntity::new(cx.waker().clone(), val)));
+            unsafe { this.parent.register(node) };
+            Poll::Pending
+        }
+    }
+}
+impl<'a, T> Drop for Listen<'a, T> {
+    fn drop(&mut self) {
+        if let Some(node) = &mut self.node {
+            self.parent.remove(node);
+        }
+    }
+}
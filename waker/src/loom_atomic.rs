@@ -0,0 +1,95 @@
+//! Atomics for the `loom` model checker, enabled by `--cfg loom`.
+//!
+//! loom atomics can not be created in const context, and one created on first use
+//! is not ordered before the accesses of other threads, so these wrap the core atomics.
+//! Every operation first writes one shared loom atomic, so loom sees all of them as
+//! dependent and may switch to another thread at each of them.
+//!
+//! So loom explores the interleavings of the atomics of this project, but not weak memory orderings:
+//! the core atomics always behave as sequentially consistent, and all of them are `SeqCst` anyway.
+//! Bound the preemptions with `LOOM_MAX_PREEMPTIONS=3`, the models do not finish without it.
+//!
+//! The waker pool and the buffers of `Ring` and `Spsc` are on these atomics.
+//! The `ach-*` crates still used (e.g. by `async_ach-cell`) use core atomics directly,
+//! so their internal states are not modeled: each of their operations runs as one step.
+
+extern crate std;
+
+use core::sync::atomic;
+
+pub use core::sync::atomic::Ordering;
+pub use loom::hint::spin_loop;
+
+loom::lazy_static! {
+    static ref SCHEDULE: loom::sync::atomic::AtomicUsize = loom::sync::atomic::AtomicUsize::new(0);
+}
+
+/// Let loom switch to another thread.
+///
+/// A load is not enough, loads of different threads are independent and loom does not reorder them.
+fn branch() {
+    SCHEDULE.fetch_add(1, Ordering::SeqCst);
+}
+
+macro_rules! atomic {
+    ($name:ident, $ty:ty) => {
+        pub struct $name(atomic::$name);
+        impl $name {
+            pub const fn new(v: $ty) -> Self {
+                Self(atomic::$name::new(v))
+            }
+            pub fn get_mut(&mut self) -> &mut $ty {
+                self.0.get_mut()
+            }
+            pub fn load(&self, order: Ordering) -> $ty {
+                branch();
+                self.0.load(order)
+            }
+            pub fn store(&self, val: $ty, order: Ordering) {
+                branch();
+                self.0.store(val, order)
+            }
+            pub fn swap(&self, val: $ty, order: Ordering) -> $ty {
+                branch();
+                self.0.swap(val, order)
+            }
+            pub fn compare_exchange(
+                &self,
+                current: $ty,
+                new: $ty,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$ty, $ty> {
+                branch();
+                self.0.compare_exchange(current, new, success, failure)
+            }
+            pub fn fetch_update<F: FnMut($ty) -> Option<$ty>>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                f: F,
+            ) -> Result<$ty, $ty> {
+                branch();
+                self.0.fetch_update(set_order, fetch_order, f)
+            }
+        }
+    };
+    ($name:ident, $ty:ty, int) => {
+        atomic!($name, $ty);
+        impl $name {
+            pub fn fetch_add(&self, val: $ty, order: Ordering) -> $ty {
+                branch();
+                self.0.fetch_add(val, order)
+            }
+            pub fn fetch_sub(&self, val: $ty, order: Ordering) -> $ty {
+                branch();
+                self.0.fetch_sub(val, order)
+            }
+        }
+    };
+}
+
+atomic!(AtomicBool, bool);
+atomic!(AtomicU8, u8, int);
+atomic!(AtomicU32, u32, int);
+atomic!(AtomicUsize, usize, int);
@@ -1,20 +1,57 @@
+use crate::atomic::{AtomicBool, Ordering};
+use crate::cell::Cell;
+use crate::spin_loop;
 use crate::WakerEntity;
-use ach_array::Array;
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::Waker;
 
 pub struct WakerToken<'a, T, const N: usize> {
     pool: &'a WakerPool<T, N>,
     index: usize,
 }
 impl<'a, T, const N: usize> WakerToken<'a, T, N> {
+    /// The index of the place in the pool.
+    pub fn index(&self) -> usize {
+        self.index
+    }
     /// Swap waker
     ///
     /// wake it immediately, if is waking.
     pub fn swap(&self, waker: WakerEntity<T>) {
         if let Err(e) = self.pool.pool[self.index].try_replace(waker) {
+            // the entry is held by a waking thread, let it go on before polling again
+            spin_loop();
             e.input.wake();
         }
     }
+    /// Returns true if the waker had been waked or removed from the pool.
+    pub fn is_waked(&self) -> bool {
+        self.pool.pool[self.index]
+            .try_get()
+            .map_or(true, |x| x.is_waked())
+    }
+    /// Set the waker of the registered entity, and keep its value.
+    ///
+    /// Returns false if there is no entity, or it is being swapped.
+    pub fn set_waker(&self, waker: &Waker) -> bool {
+        if let Ok(e) = self.pool.pool[self.index].try_get() {
+            e.set_waker(waker.clone());
+            true
+        } else {
+            false
+        }
+    }
+    /// Swap waker, and returns the old one.
+    ///
+    /// wake it immediately and returns None, if is waking.
+    pub fn replace(&self, waker: WakerEntity<T>) -> Option<WakerEntity<T>> {
+        match self.pool.pool[self.index].try_replace(waker) {
+            Ok(old) => old,
+            Err(e) => {
+                e.input.wake();
+                None
+            }
+        }
+    }
 }
 impl<'a, T, const N: usize> Drop for WakerToken<'a, T, N> {
     fn drop(&mut self) {
@@ -31,19 +68,34 @@ impl<'a, T, const N: usize> Drop for WakerToken<'a, T, N> {
 }
 
 pub struct WakerPool<T, const N: usize> {
-    pool: Array<WakerEntity<T>, N>,
+    pool: [Cell<WakerEntity<T>>; N],
     used: [AtomicBool; N],
 }
+impl<T, const N: usize> Default for WakerPool<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl<T, const N: usize> WakerPool<T, N> {
+    #[allow(clippy::declare_interior_mutable_const)]
     const FALSE: AtomicBool = AtomicBool::new(false);
+    #[allow(clippy::declare_interior_mutable_const)]
+    const EMPTY: Cell<WakerEntity<T>> = Cell::new();
     pub const fn new() -> Self {
         Self {
-            pool: Array::new(),
+            pool: [Self::EMPTY; N],
             used: [Self::FALSE; N],
         }
     }
+    /// Takes a waker out of the pool, skips the ones being swapped.
+    fn pop(&self) -> Option<WakerEntity<T>> {
+        self.pool.iter().find_map(|x| x.try_take().ok().flatten())
+    }
     /// Hold a place in the pool
-    pub fn register(&self) -> Result<WakerToken<T, N>, ()> {
+    ///
+    /// Returns Err if the pool is full.
+    #[allow(clippy::result_unit_err)]
+    pub fn register(&self) -> Result<WakerToken<'_, T, N>, ()> {
         for (i, used) in self.used.iter().enumerate() {
             if used
                 .compare_exchange(false, true, Ordering::SeqCst, Ordering::Relaxed)
@@ -62,7 +114,7 @@ impl<T, const N: usize> WakerPool<T, N> {
     /// Returns false if the pool is empty.
     pub fn wake_one(&self) -> bool {
         loop {
-            if let Some(waker) = self.pool.pop() {
+            if let Some(waker) = self.pop() {
                 if waker.wake() {
                     return true;
                 }
@@ -71,17 +123,84 @@ impl<T, const N: usize> WakerPool<T, N> {
             }
         }
     }
+    /// Wake the waiter with the highest value, and remove it.
+    ///
+    /// It scans all places of the pool, and falls back to `wake_one` if the highest waiter is being swapped.
+    ///
+    /// Returns false if there is no waiter to wake.
+    pub fn wake_highest(&self) -> bool
+    where
+        T: Ord,
+    {
+        loop {
+            // hold each entry only briefly, the owners swap their wakers meanwhile
+            let mut highest: Option<usize> = None;
+            for i in 0..N {
+                let e = match self.pool[i].try_get() {
+                    Ok(e) if !e.is_waked() => e,
+                    _ => continue,
+                };
+                let higher = match highest {
+                    Some(h) => self.pool[h].try_get().map_or(true, |h| **e > **h),
+                    None => true,
+                };
+                if higher {
+                    highest = Some(i);
+                }
+            }
+            let i = match highest {
+                Some(i) => i,
+                None => return false,
+            };
+            // take it out of the pool, so it is woken without holding the entry
+            match self.pool[i].try_take() {
+                Ok(Some(e)) => {
+                    if e.wake() {
+                        return true;
+                    }
+                }
+                // removed meanwhile
+                Ok(None) => {}
+                // being swapped or removed, do not wait for it
+                Err(_) => return self.wake_one(),
+            }
+        }
+    }
     /// Wake all waiter, and remove it.
     ///
     /// returns the number of had waked
     pub fn wake_all(&self) -> usize {
         let mut num = 0;
-        while let Some(waker) = self.pool.pop() {
+        while let Some(waker) = self.pop() {
             waker.wake();
             num += 1;
         }
         num
     }
+    /// Wake all waiters whose value matches the predicate, and keep them in the pool.
+    ///
+    /// `f` is called with the index of the waiter, see `WakerToken::index`.
+    /// The waiters being swapped are skipped, so keep the entity and use `WakerToken::set_waker`.
+    ///
+    /// returns the number of had waked
+    pub fn wake_if(&self, mut f: impl FnMut(usize, &T) -> bool) -> usize {
+        let mut num = 0;
+        for i in 0..N {
+            let e = match self.pool[i].try_get() {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            if !f(i, &e) {
+                continue;
+            }
+            if let Some(waker) = e.take_waker() {
+                drop(e);
+                waker.wake();
+                num += 1;
+            }
+        }
+        num
+    }
 
     /// Retains only the elements specified by the predicate.
     ///
@@ -89,8 +208,8 @@ impl<T, const N: usize> WakerPool<T, N> {
     /// This method operates in place, visiting each element exactly once in the original order,
     /// but not preserves the order of the retained elements.
     pub fn retain(&self, mut f: impl FnMut(&WakerEntity<T>) -> bool) {
-        for e in self.pool.iter(false) {
-            if !f(&*e) {
+        for e in self.pool.iter().filter_map(|x| x.try_get().ok()) {
+            if !f(&e) {
                 // Remove it
                 e.remove();
             }
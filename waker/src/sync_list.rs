@@ -0,0 +1,165 @@
+//! Work on multiple threads
+//!
+//! Wakers are registered by pinned `Registration`, which will be removed from the list on drop.
+//!
+//! The list is guarded by a spin lock, which is held only while a node is linked, unlinked or searched,
+//! the wakers are called after unlocking.
+
+use crate::atomic::{AtomicBool, Ordering::SeqCst};
+use crate::{spin_loop, WakerEntity};
+use ach_linked::{LinkedList, Node};
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::Waker;
+
+/// The number of wakers taken at once by `wake_all`.
+const BATCH: usize = 8;
+
+pub struct SyncWakerList<T> {
+    list: LinkedList<WakerEntity<T>>,
+    locked: AtomicBool,
+    _val: PhantomData<T>,
+}
+impl<T> Default for SyncWakerList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T> SyncWakerList<T> {
+    pub const fn new() -> Self {
+        Self {
+            list: LinkedList::new(),
+            locked: AtomicBool::new(false),
+            _val: PhantomData,
+        }
+    }
+    fn lock(&self) -> ListGuard<'_, T> {
+        while self
+            .locked
+            .compare_exchange(false, true, SeqCst, SeqCst)
+            .is_err()
+        {
+            spin_loop();
+        }
+        ListGuard { parent: self }
+    }
+    pub fn is_empty(&self) -> bool {
+        let _guard = self.lock();
+        self.list.is_empty()
+    }
+    /// Create a slot with `val`, it is linked into the list on the first `register`.
+    pub fn registration(&self, val: T) -> Registration<'_, T> {
+        Registration {
+            parent: self,
+            val: Some(val),
+            node: None,
+        }
+    }
+    /// Wake a waiter.
+    ///
+    /// Returns false if there is no waiter to wake.
+    pub fn wake_one(&self) -> bool {
+        let mut wakers = [None];
+        self.take_wakers(&mut wakers);
+        if let Some(waker) = wakers[0].take() {
+            waker.wake();
+            true
+        } else {
+            false
+        }
+    }
+    /// Wake all waiters.
+    ///
+    /// returns the number of had waked
+    pub fn wake_all(&self) -> usize {
+        let mut num = 0;
+        loop {
+            let mut wakers: [Option<Waker>; BATCH] = Default::default();
+            let len = self.take_wakers(&mut wakers);
+            for waker in wakers.iter_mut().filter_map(Option::take) {
+                waker.wake();
+            }
+            num += len;
+            if len < BATCH {
+                return num;
+            }
+        }
+    }
+    /// Take the wakers out of the list, the nodes are kept and marked as waked.
+    ///
+    /// returns the number of taken wakers
+    fn take_wakers(&self, wakers: &mut [Option<Waker>]) -> usize {
+        let _guard = self.lock();
+        let mut num = 0;
+        // the list is locked, so no one else sees it detached
+        if let Some(list) = self.list.take_all() {
+            let mut node = unsafe { &mut *(list as *mut Node<WakerEntity<T>>) };
+            while num < wakers.len() {
+                if let Some(waker) = node.take_waker() {
+                    wakers[num] = Some(waker);
+                    num += 1;
+                }
+                if let Some(next) = node.next() {
+                    node = next;
+                } else {
+                    break;
+                }
+            }
+            unsafe { self.list.push_list(list) };
+        }
+        num
+    }
+}
+
+struct ListGuard<'a, T> {
+    parent: &'a SyncWakerList<T>,
+}
+impl<'a, T> Drop for ListGuard<'a, T> {
+    fn drop(&mut self) {
+        self.parent.locked.store(false, SeqCst);
+    }
+}
+
+/// A waker slot of `SyncWakerList`.
+///
+/// It must be pinned before registering, and it is removed from the list on drop.
+pub struct Registration<'a, T> {
+    parent: &'a SyncWakerList<T>,
+    val: Option<T>,
+    node: Option<Node<WakerEntity<T>>>,
+}
+// SAFETY: the links of the node are only touched while the list's spin lock is held,
+// and the waker in it is swapped atomically, so the node can be owned by another thread.
+unsafe impl<T: Send> Send for Registration<'_, T> {}
+impl<'a, T> Registration<'a, T> {
+    /// Link the waker into the list, or swap it if linked.
+    pub fn register(self: Pin<&mut Self>, waker: &Waker) {
+        // Safety: the node is never moved, and it is removed from the list on drop.
+        let this = unsafe { self.get_unchecked_mut() };
+        if let Some(node) = &this.node {
+            node.set_waker(waker.clone());
+        } else {
+            let val = this.val.take().expect("registered twice");
+            let node = this
+                .node
+                .insert(Node::new(WakerEntity::new(waker.clone(), val)));
+            let _guard = this.parent.lock();
+            unsafe { this.parent.list.push(node) };
+        }
+    }
+    pub fn is_registered(&self) -> bool {
+        self.node.is_some()
+    }
+    /// Returns true if it had been waked since the last `register`.
+    pub fn is_waked(&self) -> bool {
+        self.node.as_ref().is_some_and(|x| x.is_waked())
+    }
+}
+impl<'a, T> Drop for Registration<'a, T> {
+    fn drop(&mut self) {
+        if let Some(node) = &mut self.node {
+            let _guard = self.parent.lock();
+            self.parent.list.remove(node);
+        }
+    }
+}
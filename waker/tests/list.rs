@@ -0,0 +1,26 @@
+use async_ach_waker::list::WakerList;
+use core::future::Future;
+use futures_test::task;
+
+#[test]
+fn test() {
+    let list: WakerList<()> = WakerList::new();
+    let mut cx = task::noop_context();
+
+    let mut listen1 = Box::pin(list.listen(()));
+    let mut listen2 = Box::pin(list.listen(()));
+    assert!(!list.wake_one());
+    assert!(listen1.as_mut().poll(&mut cx).is_pending());
+    assert!(listen2.as_mut().poll(&mut cx).is_pending());
+    assert!(list.wake_one());
+    assert_eq!(list.wake_all(), 1);
+    assert!(listen1.as_mut().poll(&mut cx).is_ready());
+    assert!(listen2.as_mut().poll(&mut cx).is_ready());
+    drop(listen1);
+
+    let mut listen3 = Box::pin(list.listen(()));
+    assert!(listen3.as_mut().poll(&mut cx).is_pending());
+    drop(listen2);
+    assert_eq!(list.wake_all(), 1);
+    assert!(listen3.as_mut().poll(&mut cx).is_ready());
+}
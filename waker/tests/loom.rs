@@ -0,0 +1,27 @@
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=3 cargo test --test loom --release`.
+#![cfg(loom)]
+
+use async_ach_waker::sync_list::SyncWakerList;
+use futures_test::task;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn wake_one_concurrently() {
+    loom::model(|| {
+        let list: Arc<SyncWakerList<()>> = Arc::new(SyncWakerList::new());
+        let (waker1, count1) = task::new_count_waker();
+        let (waker2, count2) = task::new_count_waker();
+
+        let mut reg1 = Box::pin(list.registration(()));
+        let mut reg2 = Box::pin(list.registration(()));
+        reg1.as_mut().register(&waker1);
+        reg2.as_mut().register(&waker2);
+        let notifier = list.clone();
+        let th = thread::spawn(move || assert!(notifier.wake_one()));
+        assert!(list.wake_one());
+        th.join().unwrap();
+        assert_eq!(count1.get(), 1);
+        assert_eq!(count2.get(), 1);
+    });
+}
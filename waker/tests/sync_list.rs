@@ -0,0 +1,80 @@
+use async_ach_waker::sync_list::SyncWakerList;
+use futures_test::task;
+use std::sync::Barrier;
+use std::thread;
+
+#[test]
+fn test() {
+    static LIST: SyncWakerList<()> = SyncWakerList::new();
+    let waker = task::noop_waker();
+
+    let mut reg1 = Box::pin(LIST.registration(()));
+    let mut reg2 = Box::pin(LIST.registration(()));
+    assert!(!LIST.wake_one());
+    reg1.as_mut().register(&waker);
+    reg2.as_mut().register(&waker);
+    assert!(reg1.is_registered());
+    assert!(LIST.wake_one());
+    assert!(reg1.is_waked() ^ reg2.is_waked());
+    assert_eq!(LIST.wake_all(), 1);
+    assert!(reg1.is_waked() && reg2.is_waked());
+
+    reg1.as_mut().register(&waker);
+    drop(reg2);
+    assert!(!LIST.is_empty());
+    assert_eq!(LIST.wake_all(), 1);
+    drop(reg1);
+    assert!(LIST.is_empty());
+}
+
+#[test]
+fn test_threads() {
+    const ROUNDS: usize = 10000;
+    let list = SyncWakerList::new();
+    let start = Barrier::new(3);
+    let end = Barrier::new(3);
+    let (waker1, count1) = task::new_count_waker();
+    let (waker2, count2) = task::new_count_waker();
+
+    let mut reg1 = Box::pin(list.registration(()));
+    let mut reg2 = Box::pin(list.registration(()));
+    thread::scope(|s| {
+        let notify = || {
+            for _ in 0..ROUNDS {
+                start.wait();
+                // no wakeup is lost while the other notifier walks the list
+                assert!(list.wake_one());
+                end.wait();
+            }
+        };
+        s.spawn(notify);
+        s.spawn(notify);
+        for round in 1..=ROUNDS {
+            reg1.as_mut().register(&waker1);
+            reg2.as_mut().register(&waker2);
+            start.wait();
+            end.wait();
+            assert_eq!(count1.get(), round);
+            assert_eq!(count2.get(), round);
+        }
+    });
+}
+
+#[test]
+fn send() {
+    fn assert_send<T: Send>() {}
+    assert_send::<async_ach_waker::sync_list::Registration<'static, usize>>();
+
+    static LIST: SyncWakerList<usize> = SyncWakerList::new();
+    let mut reg = Box::pin(LIST.registration(1));
+    reg.as_mut().register(&task::noop_waker());
+    let reg = thread::spawn(move || {
+        assert!(LIST.wake_one());
+        reg
+    })
+    .join()
+    .unwrap();
+    assert!(reg.is_waked());
+    drop(reg);
+    assert!(LIST.is_empty());
+}
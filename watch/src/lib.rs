@@ -2,29 +2,35 @@
 
 use ach_util::Error;
 use async_ach_cell::Cell;
-use async_ach_notify::{Listener, Notify};
+use async_ach_notify::{Notifier, Notify};
+use atomic::{AtomicUsize, Ordering::SeqCst};
 use core::future::Future;
 use core::pin::Pin;
-use core::sync::atomic::{AtomicUsize, Ordering::SeqCst};
 use core::task::{Context, Poll};
 use core::{ops::Range, time::Duration};
 use futures_util::Stream;
 
-pub struct Watch<T, const W: usize> {
+#[cfg(loom)]
+use async_ach_notify::loom_atomic as atomic;
+#[cfg(not(loom))]
+use core::sync::atomic;
+
+/// `P` is the notify backend of subscribers, use `IntrusiveNotify` if the number is unknown.
+pub struct Watch<T, const W: usize, P = Notify<W>> {
     val: Cell<T, 1, 1>,
     version: AtomicUsize,
-    producer: Notify<W>,
+    producer: P,
 }
-impl<T, const W: usize> Watch<T, W> {
+impl<T, const W: usize, P: Notifier> Watch<T, W, P> {
     pub const fn new(init: T) -> Self {
         Self {
             val: Cell::new_with(init),
             version: AtomicUsize::new(0),
-            producer: Notify::new(),
+            producer: P::NEW,
         }
     }
 }
-impl<T: Unpin + Clone, const W: usize> Watch<T, W> {
+impl<T: Unpin + Clone, const W: usize, P: Notifier> Watch<T, W, P> {
     /// Get a copy of the value.
     pub fn data(&self) -> T {
         unsafe { self.val.peek().clone() }
@@ -35,7 +41,7 @@ impl<T: Unpin + Clone, const W: usize> Watch<T, W> {
     pub fn try_send(&self, value: T) -> Result<(), Error<T>> {
         self.val.try_replace(value)?;
         self.version.fetch_add(1, SeqCst);
-        self.producer.notify_waiters();
+        self.producer.notify_all();
         Ok(())
     }
     /// Update the watch
@@ -44,11 +50,10 @@ impl<T: Unpin + Clone, const W: usize> Watch<T, W> {
         self.version.fetch_add(1, SeqCst);
         self.producer.notify_one();
     }
-    pub fn subscribe(&self) -> Receiver<'_, T, W> {
+    pub fn subscribe(&self) -> Receiver<'_, T, W, P> {
         Receiver {
             parent: self,
             version: self.version.load(SeqCst),
-            wait_p: self.producer.listen(),
         }
     }
     fn update(&self, version: &mut usize) -> bool {
@@ -62,21 +67,24 @@ impl<T: Unpin + Clone, const W: usize> Watch<T, W> {
     }
 }
 
-pub struct Receiver<'a, T, const W: usize> {
-    parent: &'a Watch<T, W>,
+pub struct Receiver<'a, T, const W: usize, P = Notify<W>> {
+    parent: &'a Watch<T, W, P>,
     version: usize,
-    wait_p: Listener<'a, W>,
 }
-impl<'a, T: Unpin + Clone, const W: usize> Receiver<'a, T, W> {
+impl<'a, T: Unpin + Clone, const W: usize, P: Notifier> Receiver<'a, T, W, P> {
     /// Get a copy of the value.
     pub fn data(&self) -> T {
         self.parent.data()
     }
-    pub fn changed<'b>(&'b mut self) -> Changed<'b, 'a, T, W> {
+    pub fn changed<'b>(&'b mut self) -> Changed<'b, 'a, T, W, P> {
         self.changed_interval(Duration::ZERO..Duration::MAX)
     }
-    pub fn changed_interval<'b>(&'b mut self, interval: Range<Duration>) -> Changed<'b, 'a, T, W> {
+    pub fn changed_interval<'b>(
+        &'b mut self,
+        interval: Range<Duration>,
+    ) -> Changed<'b, 'a, T, W, P> {
         Changed {
+            wait_p: self.parent.producer.listen(),
             parent: self,
             interval,
             last_time: 0,
@@ -85,57 +93,61 @@ impl<'a, T: Unpin + Clone, const W: usize> Receiver<'a, T, W> {
     }
 }
 
-pub struct Changed<'b, 'a, T, const W: usize> {
-    parent: &'b mut Receiver<'a, T, W>,
+/// It is `!Unpin` if the listener of `P` is.
+pub struct Changed<'b, 'a, T, const W: usize, P: Notifier = Notify<W>> {
+    parent: &'b mut Receiver<'a, T, W, P>,
+    wait_p: P::Listener<'a>,
     interval: Range<Duration>,
     last_time: u64,
     sleep: Option<async_tick::Sleep>,
 }
-impl<'b, 'a, T: Unpin + Clone, const W: usize> Stream for Changed<'b, 'a, T, W> {
+impl<'b, 'a, T: Unpin + Clone, const W: usize, P: Notifier> Stream for Changed<'b, 'a, T, W, P> {
     type Item = T;
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Safety: the listener is never moved out
+        let this = unsafe { self.get_unchecked_mut() };
         let now = async_tick::now();
-        if self.interval.start != Duration::ZERO {
-            let start = self.last_time + self.interval.start.as_nanos() as u64;
+        if this.interval.start != Duration::ZERO {
+            let start = this.last_time + this.interval.start.as_nanos() as u64;
             if now < start {
                 let mut sleep = async_tick::sleep_until(start);
                 if Pin::new(&mut sleep).poll(cx).is_pending() {
-                    self.sleep = Some(sleep);
+                    this.sleep = Some(sleep);
                     return Poll::Pending;
                 }
             }
         }
-        if self.interval.end != Duration::MAX {
-            let end = self.last_time + self.interval.end.as_nanos() as u64;
+        if this.interval.end != Duration::MAX {
+            let end = this.last_time + this.interval.end.as_nanos() as u64;
             if now > end {
-                self.last_time = now;
-                return Poll::Ready(Some(self.parent.data()));
+                this.last_time = now;
+                return Poll::Ready(Some(this.parent.data()));
             }
         }
 
-        if self.parent.parent.update(&mut self.parent.version) {
-            self.last_time = now;
-            return Poll::Ready(Some(self.parent.data()));
+        if this.parent.parent.update(&mut this.parent.version) {
+            this.last_time = now;
+            return Poll::Ready(Some(this.parent.data()));
         }
-        if self.interval.end != Duration::MAX {
-            let end = self.last_time + self.interval.end.as_nanos() as u64;
+        if this.interval.end != Duration::MAX {
+            let end = this.last_time + this.interval.end.as_nanos() as u64;
             let mut sleep = async_tick::sleep_until(end);
             if Pin::new(&mut sleep).poll(cx).is_ready() {
-                self.last_time = now;
-                return Poll::Ready(Some(self.parent.data()));
+                this.last_time = now;
+                return Poll::Ready(Some(this.parent.data()));
             }
-            self.sleep = Some(sleep);
+            this.sleep = Some(sleep);
         }
-        let _ = Pin::new(&mut self.parent.wait_p).poll_next(cx);
-        if self.parent.parent.update(&mut self.parent.version) {
-            self.last_time = now;
-            Poll::Ready(Some(self.parent.data()))
+        let _ = unsafe { Pin::new_unchecked(&mut this.wait_p) }.poll_next(cx);
+        if this.parent.parent.update(&mut this.parent.version) {
+            this.last_time = now;
+            Poll::Ready(Some(this.parent.data()))
         } else {
             Poll::Pending
         }
     }
 }
-impl<'b, 'a, T: Unpin + Clone, const W: usize> Future for Changed<'b, 'a, T, W> {
+impl<'b, 'a, T: Unpin + Clone, const W: usize, P: Notifier> Future for Changed<'b, 'a, T, W, P> {
     type Output = T;
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match self.poll_next(cx) {
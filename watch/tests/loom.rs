@@ -0,0 +1,20 @@
+//! Run with `RUSTFLAGS="--cfg loom" LOOM_MAX_PREEMPTIONS=3 cargo test --test loom --release`.
+#![cfg(loom)]
+
+use async_ach_watch::Watch;
+use loom::future::block_on;
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn send_changed() {
+    loom::model(|| {
+        let watch: Arc<Watch<usize, 1>> = Arc::new(Watch::new(0));
+        let mut rx = watch.subscribe();
+
+        let sender = watch.clone();
+        let th = thread::spawn(move || sender.try_send(1).unwrap());
+        assert_eq!(block_on(rx.changed()), 1);
+        th.join().unwrap();
+    });
+}